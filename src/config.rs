@@ -1,22 +1,88 @@
-use anyhow::Result;
-use std::env;
+use anyhow::{Result, bail};
+use std::{env, path::PathBuf, time::Duration};
+
+use crate::domain::ledger::{DEFAULT_HOLD_TTL, DEFAULT_MAX_BACKDATE, DEFAULT_MAX_FORWARD_DATE, DEFAULT_SNAPSHOT_INTERVAL, MAX_HOLD_TTL};
+
+/// Where the ledger keeps its events.
+#[derive(Debug, Clone)]
+pub enum Storage {
+    InMemory,
+    Sqlite(PathBuf),
+    Journal(PathBuf),
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub http_port: u16,
+    pub storage: Storage,
+    pub snapshot_interval: u64,
+    pub fx_rates_path: Option<PathBuf>,
+    pub hold_ttl: Duration,
+    pub max_backdate: Duration,
+    pub max_forward_date: Duration,
 }
 
 impl Config {
     /// Load configuration from environment variables.
     /// - `HTTP_PORT` (optional, defaults to 8080)
+    /// - `LEDGER_DB_PATH` (optional, SQLite event store)
+    /// - `LEDGER_JOURNAL_PATH` (optional, JSONL journal file)
+    /// - `LEDGER_SNAPSHOT_INTERVAL` (optional, events between balance snapshots, defaults to 100)
+    /// - `LEDGER_FX_RATES_PATH` (optional, JSON rate table for currency conversions)
+    /// - `LEDGER_HOLD_TTL_SECS` (optional, seconds before an uncaptured hold expires, defaults to 7 days, at most 100 years)
+    /// - `LEDGER_MAX_BACKDATE_DAYS` (optional, how many days before today a value date may be, defaults to 30)
+    /// - `LEDGER_MAX_FORWARD_DATE_DAYS` (optional, how many days after today a value date may be, defaults to 7)
+    ///
+    /// At most one of the storage paths may be set; events are kept in memory only if neither is.
     pub fn from_env() -> Result<Self> {
         let http_port = env::var("HTTP_PORT")
             .ok()
             .and_then(|s| s.parse::<u16>().ok())
             .unwrap_or(8080);
 
+        let storage = match (env::var_os("LEDGER_DB_PATH"), env::var_os("LEDGER_JOURNAL_PATH")) {
+            (Some(_), Some(_)) => bail!("LEDGER_DB_PATH and LEDGER_JOURNAL_PATH cannot both be set"),
+            (Some(path), None) => Storage::Sqlite(path.into()),
+            (None, Some(path)) => Storage::Journal(path.into()),
+            (None, None) => Storage::InMemory,
+        };
+
+        let snapshot_interval = env::var("LEDGER_SNAPSHOT_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL);
+
+        let fx_rates_path = env::var_os("LEDGER_FX_RATES_PATH").map(PathBuf::from);
+
+        let hold_ttl = match env::var("LEDGER_HOLD_TTL_SECS") {
+            Ok(secs) => match secs.parse::<u64>().map(Duration::from_secs) {
+                Ok(ttl) if ttl <= MAX_HOLD_TTL => ttl,
+                _ => bail!("LEDGER_HOLD_TTL_SECS must be a number of seconds up to {}, got {secs}", MAX_HOLD_TTL.as_secs()),
+            },
+            Err(_) => DEFAULT_HOLD_TTL,
+        };
+
+        let days = |var: &str, default: Duration| {
+            match env::var(var) {
+                Ok(days) => match days.parse::<u64>() {
+                    Ok(days) => Ok(Duration::from_secs(days.saturating_mul(24 * 60 * 60))),
+                    Err(_) => bail!("{var} must be a number of days, got {days}"),
+                },
+                Err(_) => Ok(default),
+            }
+        };
+
+        let max_backdate = days("LEDGER_MAX_BACKDATE_DAYS", DEFAULT_MAX_BACKDATE)?;
+        let max_forward_date = days("LEDGER_MAX_FORWARD_DATE_DAYS", DEFAULT_MAX_FORWARD_DATE)?;
+
         Ok(Self {
             http_port,
+            storage,
+            snapshot_interval,
+            fx_rates_path,
+            hold_ttl,
+            max_backdate,
+            max_forward_date,
         })
     }
 }
@@ -0,0 +1,217 @@
+use core::fmt;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Currency, Money, MoneyError, SignedMoney, chart::AccountType, journal::Side, status::AccountStatus, types::HoldId};
+
+/// An account's sub-balances, one per currency it holds, the overdraft limit
+/// arranged on each, the holds reserving part of them, and whether money may
+/// move in or out at all.
+///
+/// A currency the account has never held reads as zero with no overdraft. Sub-balances
+/// are independent: money in one currency never covers a movement in another. Each is
+/// signed by the account type's normal side, so a credit adds to a liability's balance
+/// and takes from an asset's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Balances {
+    /// Deliberately not defaulted: snapshots from before account types were recorded
+    /// fail to load and are rebuilt from the events instead.
+    account_type: AccountType,
+    balances: BTreeMap<Currency, SignedMoney>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    overdraft_limits: BTreeMap<Currency, Money>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    holds: BTreeMap<HoldId, Money>,
+    #[serde(default, skip_serializing_if = "AccountStatus::is_open")]
+    status: AccountStatus,
+}
+
+impl Balances {
+    /// Balances of a newly opened account: zero in its opening currency.
+    pub fn opened_in(currency: Currency, account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            balances: BTreeMap::from([(currency, SignedMoney::zero(currency))]),
+            overdraft_limits: BTreeMap::new(),
+            holds: BTreeMap::new(),
+            status: AccountStatus::Open,
+        }
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+
+    /// The ledger balance in `currency`, negative when overdrawn.
+    pub fn get(&self, currency: Currency) -> SignedMoney {
+        self.balances.get(&currency).copied().unwrap_or(SignedMoney::zero(currency))
+    }
+
+    pub fn overdraft_limit(&self, currency: Currency) -> Money {
+        self.overdraft_limits.get(&currency).copied().unwrap_or(Money::zero(currency))
+    }
+
+    /// The amount still reserved by an open hold.
+    pub fn hold(&self, hold_id: HoldId) -> Option<Money> {
+        self.holds.get(&hold_id).copied()
+    }
+
+    /// Total reserved by open holds in `currency`.
+    pub fn held(&self, currency: Currency) -> Result<Money, MoneyError> {
+        self.holds
+            .values()
+            .filter(|hold| hold.currency() == currency)
+            .try_fold(Money::zero(currency), |total, hold| total.checked_add(*hold))
+    }
+
+    /// How much can still be taken out in `currency`: the balance plus the overdraft limit,
+    /// less anything held. Negative if a lowered limit left the account over it.
+    pub fn available(&self, currency: Currency) -> Result<SignedMoney, MoneyError> {
+        self.get(currency).checked_add(self.overdraft_limit(currency))?.checked_sub(self.held(currency)?)
+    }
+
+    pub fn credit(&mut self, amount: Money) -> Result<(), MoneyError> {
+        self.post(Side::Credit, amount)
+    }
+
+    /// Post `amount` to the debit side. Whether the account may go that far overdrawn is
+    /// for the caller to check.
+    pub fn debit(&mut self, amount: Money) -> Result<(), MoneyError> {
+        self.post(Side::Debit, amount)
+    }
+
+    /// Add `amount` to the balance if `side` is the account's normal side, otherwise take it off.
+    fn post(&mut self, side: Side, amount: Money) -> Result<(), MoneyError> {
+        let balance = self.get(amount.currency());
+
+        let balance = if side == self.account_type.normal_side() {
+            balance.checked_add(amount)?
+        } else {
+            balance.checked_sub(amount)?
+        };
+
+        self.balances.insert(amount.currency(), balance);
+        Ok(())
+    }
+
+    /// Replace the overdraft limit in the limit's currency. A zero limit removes it.
+    pub fn set_overdraft_limit(&mut self, limit: Money) {
+        if limit.amount() == 0 {
+            self.overdraft_limits.remove(&limit.currency());
+        } else {
+            self.overdraft_limits.insert(limit.currency(), limit);
+        }
+    }
+
+    pub fn status(&self) -> AccountStatus {
+        self.status
+    }
+
+    /// Whether every sub-balance is zero and nothing is held, as it must be to close.
+    pub fn is_settled(&self) -> bool {
+        self.holds.is_empty() && self.iter().all(|balance| balance.amount() == 0)
+    }
+
+    pub fn set_status(&mut self, status: AccountStatus) {
+        self.status = status;
+    }
+
+    /// Reserve `amount` without taking it off the balance. Whether it is available is
+    /// for the caller to check.
+    pub fn place_hold(&mut self, hold_id: HoldId, amount: Money) {
+        self.holds.insert(hold_id, amount);
+    }
+
+    /// Release a hold, returning what it reserved, or `None` if it wasn't open.
+    pub fn release_hold(&mut self, hold_id: HoldId) -> Option<Money> {
+        self.holds.remove(&hold_id)
+    }
+
+    /// Sub-balances ordered by currency code.
+    pub fn iter(&self) -> impl Iterator<Item = SignedMoney> + '_ {
+        self.balances.values().copied()
+    }
+}
+
+impl fmt::Display for Balances {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, balance) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{balance}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_balances_are_kept_apart() {
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+
+        balances.credit(Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        balances.credit(Money::new_minor(5_00, Currency::Eur).unwrap()).unwrap();
+
+        assert_eq!(balances.get(Currency::Gbp).amount(), 10_00);
+        assert_eq!(balances.get(Currency::Eur).amount(), 5_00);
+        assert_eq!(balances.get(Currency::Usd), SignedMoney::zero(Currency::Usd));
+    }
+
+    #[test]
+    fn overdraft_limit_adds_to_what_is_available() {
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+        balances.set_overdraft_limit(Money::new_minor(50_00, Currency::Gbp).unwrap());
+        balances.debit(Money::new_minor(20_00, Currency::Gbp).unwrap()).unwrap();
+
+        assert_eq!(balances.get(Currency::Gbp).amount(), -20_00);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 30_00);
+
+        // The limit only applies to its own currency.
+        assert_eq!(balances.available(Currency::Eur).unwrap().amount(), 0);
+
+        balances.set_overdraft_limit(Money::zero(Currency::Gbp));
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), -20_00);
+    }
+
+    #[test]
+    fn holds_reduce_what_is_available_but_not_the_balance() {
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+        balances.credit(Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let hold = HoldId::new_v4();
+        balances.place_hold(hold, Money::new_minor(30_00, Currency::Gbp).unwrap());
+        balances.place_hold(HoldId::new_v4(), Money::new_minor(5_00, Currency::Eur).unwrap());
+
+        assert_eq!(balances.get(Currency::Gbp).amount(), 10_000);
+        assert_eq!(balances.held(Currency::Gbp).unwrap().amount(), 30_00);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 70_00);
+        assert_eq!(balances.available(Currency::Eur).unwrap().amount(), -5_00);
+
+        assert_eq!(balances.release_hold(hold), Some(Money::new_minor(30_00, Currency::Gbp).unwrap()));
+        assert_eq!(balances.release_hold(hold), None);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 10_000);
+    }
+
+    #[test]
+    fn debits_add_to_debit_normal_accounts() {
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Expense);
+        balances.debit(Money::new_minor(40_00, Currency::Gbp).unwrap()).unwrap();
+        balances.credit(Money::new_minor(15_00, Currency::Gbp).unwrap()).unwrap();
+
+        assert_eq!(balances.get(Currency::Gbp).amount(), 25_00);
+    }
+
+    #[test]
+    fn display_lists_every_currency() {
+        let mut balances = Balances::opened_in(Currency::Usd, AccountType::Liability);
+        balances.credit(Money::new_minor(1_50, Currency::Gbp).unwrap()).unwrap();
+
+        assert_eq!(balances.to_string(), "£1.50, $0.00");
+    }
+}
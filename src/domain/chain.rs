@@ -0,0 +1,214 @@
+use core::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+use crate::domain::{events::{LedgerEvent, LedgerEventPayload}, types::{AccountId, EventId}};
+
+/// SHA-256 digest linking an event into the ledger's hash chain.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EventHash([u8; 32]);
+
+impl EventHash {
+    /// The `prev_hash` of the very first event, and the hash of events that haven't been sealed.
+    pub const ZERO: Self = Self([0; 32]);
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for EventHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for EventHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for EventHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EventHash({self})")
+    }
+}
+
+impl FromStr for EventHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(format!("expected 64 hex characters, got {s:?}"));
+        }
+
+        let mut bytes = [0; 32];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|e| e.to_string())?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for EventHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The fields covered by an event's hash, in a fixed order.
+#[derive(Serialize)]
+struct Canonical<'a> {
+    prev_hash: &'a EventHash,
+    id: &'a EventId,
+    account_id: &'a AccountId,
+    created_at: &'a OffsetDateTime,
+    payload: &'a LedgerEventPayload,
+}
+
+impl LedgerEvent {
+    /// Hash of the event's canonical serialization, including the link to its predecessor.
+    pub fn compute_hash(&self) -> EventHash {
+        let canonical = Canonical {
+            prev_hash: &self.prev_hash,
+            id: &self.id,
+            account_id: &self.account_id,
+            created_at: &self.created_at,
+            payload: &self.payload,
+        };
+
+        let bytes = serde_json::to_vec(&canonical).expect("ledger events always serialize");
+
+        EventHash(Sha256::digest(bytes).into())
+    }
+
+    /// Link the event onto the chain after `prev_hash` and compute its own hash.
+    pub fn seal(&mut self, prev_hash: EventHash) {
+        self.prev_hash = prev_hash;
+        self.hash = self.compute_hash();
+    }
+}
+
+/// Seal a batch of events in order, starting after `head`.
+pub fn seal_all(mut head: EventHash, events: &[LedgerEvent]) -> Vec<LedgerEvent> {
+    events
+        .iter()
+        .map(|event| {
+            let mut event = event.clone();
+            event.seal(head);
+            head = event.hash;
+            event
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakReason {
+    /// `prev_hash` doesn't match the hash of the event before it.
+    BrokenLink,
+    /// The stored hash doesn't match the event's contents.
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainBreak {
+    /// Zero-based position of the event in the global log.
+    pub position: u64,
+    pub event_id: EventId,
+    pub reason: BreakReason,
+}
+
+/// Walk the whole log in order and report the first event that breaks the chain, if any.
+pub fn verify(events: &[LedgerEvent]) -> Option<ChainBreak> {
+    let mut expected_prev = EventHash::ZERO;
+
+    for (position, event) in events.iter().enumerate() {
+        let reason = if event.prev_hash != expected_prev {
+            Some(BreakReason::BrokenLink)
+        } else if event.hash != event.compute_hash() {
+            Some(BreakReason::HashMismatch)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            return Some(ChainBreak { position: position as u64, event_id: event.id, reason });
+        }
+
+        expected_prev = event.hash;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Currency, Money};
+
+    fn sealed_log() -> Vec<LedgerEvent> {
+        let account_id = AccountId::new_v4();
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+
+        seal_all(EventHash::ZERO, &[
+            LedgerEvent::account_opened(account_id, Currency::Gbp),
+            LedgerEvent::deposit(account_id, ten),
+            LedgerEvent::withdraw(account_id, ten),
+        ])
+    }
+
+    #[test]
+    fn sealed_log_verifies() {
+        let events = sealed_log();
+
+        assert_eq!(events[1].prev_hash, events[0].hash);
+        assert_eq!(verify(&events), None);
+    }
+
+    #[test]
+    fn edited_payload_is_reported() {
+        let mut events = sealed_log();
+        events[1].payload = LedgerEventPayload::Deposit { amount: Money::new_minor(99_00, Currency::Gbp).unwrap(), via: None, value_date: None };
+
+        let broken = verify(&events).unwrap();
+        assert_eq!(broken.position, 1);
+        assert_eq!(broken.event_id, events[1].id);
+        assert_eq!(broken.reason, BreakReason::HashMismatch);
+    }
+
+    #[test]
+    fn resealed_edit_breaks_the_next_link() {
+        let mut events = sealed_log();
+        events[1].created_at -= time::Duration::days(1);
+        let prev = events[1].prev_hash;
+        events[1].seal(prev);
+
+        let broken = verify(&events).unwrap();
+        assert_eq!(broken.position, 2);
+        assert_eq!(broken.reason, BreakReason::BrokenLink);
+    }
+
+    #[test]
+    fn hash_round_trips_through_hex() {
+        let hash = sealed_log()[0].hash;
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json.len(), 64 + 2);
+        assert_eq!(serde_json::from_str::<EventHash>(&json).unwrap(), hash);
+    }
+}
@@ -0,0 +1,198 @@
+//! Chart of accounts and the financial statements drawn from it.
+//!
+//! Every account has an [`AccountType`] saying what it records for the business
+//! running the ledger. Assets and expenses are debit-normal: a debit adds to their
+//! balance and a credit takes from it. Liabilities, equity and income are
+//! credit-normal, the other way round. Customer wallets are money the business owes,
+//! so accounts opened without a type are liabilities.
+//!
+//! In a balanced ledger assets equal liabilities plus equity plus whatever income,
+//! less expenses, has not yet been moved into equity, which [`BalanceSheet`] shows.
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::domain::{Currency, MoneyError, SignedMoney, journal::{Side, SystemAccount}, types::AccountId};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountType {
+    Asset,
+    #[default]
+    Liability,
+    Equity,
+    Income,
+    Expense,
+}
+
+impl AccountType {
+    /// The side that adds to an account of this type.
+    pub fn normal_side(&self) -> Side {
+        match self {
+            AccountType::Asset | AccountType::Expense => Side::Debit,
+            AccountType::Liability | AccountType::Equity | AccountType::Income => Side::Credit,
+        }
+    }
+
+    /// The type `account_id` was opened with. Accounts opened before types were recorded
+    /// are liabilities, except the system accounts, which have always been what they are.
+    pub fn recorded_for(account_id: AccountId, recorded: Option<AccountType>) -> AccountType {
+        recorded
+            .or_else(|| SystemAccount::from_id(account_id).map(|system| system.account_type()))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AccountType::Asset => "asset",
+            AccountType::Liability => "liability",
+            AccountType::Equity => "equity",
+            AccountType::Income => "income",
+            AccountType::Expense => "expense",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// One account's balance in one currency, signed by the account type's normal side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementLine {
+    pub account_id: AccountId,
+    pub code: Option<String>,
+    pub parent_code: Option<String>,
+    pub name: Option<String>,
+    pub amount: SignedMoney,
+}
+
+/// What the business owns and owes at the end of a period, and the earnings that
+/// make up the difference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceSheet {
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+    pub assets: Vec<StatementLine>,
+    pub liabilities: Vec<StatementLine>,
+    pub equity: Vec<StatementLine>,
+    /// Income less expenses from before the period.
+    pub retained_earnings: BTreeMap<Currency, SignedMoney>,
+    /// Income less expenses during the period, as on the [`IncomeStatement`] for it.
+    pub net_income: BTreeMap<Currency, SignedMoney>,
+}
+
+impl BalanceSheet {
+    /// Whether, in every currency, assets equal liabilities plus equity plus earnings.
+    pub fn is_balanced(&self) -> Result<bool, MoneyError> {
+        let assets = totals(&self.assets)?;
+        let mut claims = totals(&self.liabilities)?;
+
+        for (currency, amount) in totals(&self.equity)?.into_iter().chain(self.retained_earnings.clone()).chain(self.net_income.clone()) {
+            let total = claims.get(&currency).copied().unwrap_or(SignedMoney::zero(currency));
+            claims.insert(currency, total.checked_add(amount)?);
+        }
+
+        Ok(assets.keys().chain(claims.keys()).all(|currency| {
+            let amount = |side: &BTreeMap<Currency, SignedMoney>| side.get(currency).map_or(0, |total| total.amount());
+            amount(&assets) == amount(&claims)
+        }))
+    }
+}
+
+/// How much each income and expense account moved over a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncomeStatement {
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+    pub income: Vec<StatementLine>,
+    pub expenses: Vec<StatementLine>,
+}
+
+impl IncomeStatement {
+    /// Income less expenses in each currency, negative for a loss.
+    pub fn net_income(&self) -> Result<BTreeMap<Currency, SignedMoney>, MoneyError> {
+        let mut net = totals(&self.income)?;
+
+        for (currency, expenses) in totals(&self.expenses)? {
+            let total = net.get(&currency).copied().unwrap_or(SignedMoney::zero(currency));
+            net.insert(currency, total.checked_sub(expenses)?);
+        }
+
+        Ok(net)
+    }
+}
+
+/// The lines added up per currency.
+pub fn totals(lines: &[StatementLine]) -> Result<BTreeMap<Currency, SignedMoney>, MoneyError> {
+    let mut totals = BTreeMap::new();
+
+    for line in lines {
+        let currency = line.amount.currency();
+        let total = totals.get(&currency).copied().unwrap_or(SignedMoney::zero(currency));
+        totals.insert(currency, total.checked_add(line.amount)?);
+    }
+
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Money;
+
+    fn line(amount_minor: i64, currency: Currency) -> StatementLine {
+        StatementLine {
+            account_id: AccountId::new_v4(),
+            code: None,
+            parent_code: None,
+            name: None,
+            amount: Money::new_minor(amount_minor, currency).unwrap().into(),
+        }
+    }
+
+    #[test]
+    fn accounts_without_a_recorded_type_are_liabilities_unless_system() {
+        assert_eq!(AccountType::recorded_for(AccountId::new_v4(), None), AccountType::Liability);
+        assert_eq!(AccountType::recorded_for(SystemAccount::CashInBank.id(), None), AccountType::Asset);
+        assert_eq!(AccountType::recorded_for(AccountId::new_v4(), Some(AccountType::Expense)), AccountType::Expense);
+
+        assert_eq!(AccountType::Expense.normal_side(), Side::Debit);
+        assert_eq!(AccountType::Income.normal_side(), Side::Credit);
+    }
+
+    #[test]
+    fn net_income_is_income_less_expenses_per_currency() {
+        let statement = IncomeStatement {
+            from: OffsetDateTime::UNIX_EPOCH,
+            to: OffsetDateTime::UNIX_EPOCH,
+            income: vec![line(10_000, Currency::Gbp), line(20_00, Currency::Gbp)],
+            expenses: vec![line(15_000, Currency::Gbp), line(5_00, Currency::Eur)],
+        };
+
+        let net = statement.net_income().unwrap();
+
+        assert_eq!(net[&Currency::Gbp].amount(), -30_00);
+        assert_eq!(net[&Currency::Eur].amount(), -5_00);
+    }
+
+    #[test]
+    fn balance_sheet_balances_when_assets_match_every_claim() {
+        let mut sheet = BalanceSheet {
+            from: OffsetDateTime::UNIX_EPOCH,
+            to: OffsetDateTime::UNIX_EPOCH,
+            assets: vec![line(10_000, Currency::Gbp)],
+            liabilities: vec![line(60_00, Currency::Gbp)],
+            equity: vec![line(25_00, Currency::Gbp)],
+            retained_earnings: BTreeMap::from([(Currency::Gbp, Money::new_minor(10_00, Currency::Gbp).unwrap().into())]),
+            net_income: BTreeMap::from([(Currency::Gbp, Money::new_minor(5_00, Currency::Gbp).unwrap().into())]),
+        };
+
+        assert!(sheet.is_balanced().unwrap());
+
+        sheet.liabilities.push(line(1, Currency::Eur));
+        assert!(!sheet.is_balanced().unwrap());
+    }
+}
@@ -0,0 +1,330 @@
+//! ISO 4217 currencies.
+//!
+//! Every active currency with a defined minor unit is listed, with its numeric
+//! code and exponent (the number of decimal places in its minor unit). Funds and
+//! precious metals codes such as `XAU` have no minor unit and are left out.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::MoneyError;
+
+macro_rules! currencies {
+    ($($variant:ident => $code:literal, $numeric:literal, $exponent:literal $(, $symbol:literal)?;)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(rename_all = "UPPERCASE")]
+        pub enum Currency {
+            $($variant,)*
+        }
+
+        impl Currency {
+            /// Every supported currency, in alphabetical order of code.
+            pub const ALL: &[Currency] = &[$(Currency::$variant,)*];
+
+            /// Three-letter alphabetic code, e.g. `GBP`.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Currency::$variant => $code,)*
+                }
+            }
+
+            /// Three-digit numeric code, e.g. 826 for GBP.
+            pub fn numeric_code(&self) -> u16 {
+                match self {
+                    $(Currency::$variant => $numeric,)*
+                }
+            }
+
+            /// Decimal places in the minor unit: 2 for GBP, 0 for JPY, 3 for KWD.
+            pub fn exponent(&self) -> u32 {
+                match self {
+                    $(Currency::$variant => $exponent,)*
+                }
+            }
+
+            fn own_symbol(&self) -> Option<&'static str> {
+                match self {
+                    $(Currency::$variant => currencies!(@symbol $($symbol)?),)*
+                }
+            }
+        }
+    };
+    (@symbol) => { None };
+    (@symbol $symbol:literal) => { Some($symbol) };
+}
+
+currencies! {
+    Aed => "AED", 784, 2;
+    Afn => "AFN", 971, 2;
+    All => "ALL", 8, 2;
+    Amd => "AMD", 51, 2;
+    Aoa => "AOA", 973, 2;
+    Ars => "ARS", 32, 2;
+    Aud => "AUD", 36, 2, "A$";
+    Awg => "AWG", 533, 2;
+    Azn => "AZN", 944, 2;
+    Bam => "BAM", 977, 2;
+    Bbd => "BBD", 52, 2;
+    Bdt => "BDT", 50, 2;
+    Bgn => "BGN", 975, 2;
+    Bhd => "BHD", 48, 3;
+    Bif => "BIF", 108, 0;
+    Bmd => "BMD", 60, 2;
+    Bnd => "BND", 96, 2;
+    Bob => "BOB", 68, 2;
+    Bov => "BOV", 984, 2;
+    Brl => "BRL", 986, 2, "R$";
+    Bsd => "BSD", 44, 2;
+    Btn => "BTN", 64, 2;
+    Bwp => "BWP", 72, 2;
+    Byn => "BYN", 933, 2;
+    Bzd => "BZD", 84, 2;
+    Cad => "CAD", 124, 2, "CA$";
+    Cdf => "CDF", 976, 2;
+    Che => "CHE", 947, 2;
+    Chf => "CHF", 756, 2;
+    Chw => "CHW", 948, 2;
+    Clf => "CLF", 990, 4;
+    Clp => "CLP", 152, 0;
+    Cny => "CNY", 156, 2, "CN¥";
+    Cop => "COP", 170, 2;
+    Cou => "COU", 970, 2;
+    Crc => "CRC", 188, 2;
+    Cup => "CUP", 192, 2;
+    Cve => "CVE", 132, 2;
+    Czk => "CZK", 203, 2;
+    Djf => "DJF", 262, 0;
+    Dkk => "DKK", 208, 2;
+    Dop => "DOP", 214, 2;
+    Dzd => "DZD", 12, 2;
+    Egp => "EGP", 818, 2;
+    Ern => "ERN", 232, 2;
+    Etb => "ETB", 230, 2;
+    Eur => "EUR", 978, 2, "€";
+    Fjd => "FJD", 242, 2;
+    Fkp => "FKP", 238, 2;
+    Gbp => "GBP", 826, 2, "£";
+    Gel => "GEL", 981, 2;
+    Ghs => "GHS", 936, 2;
+    Gip => "GIP", 292, 2;
+    Gmd => "GMD", 270, 2;
+    Gnf => "GNF", 324, 0;
+    Gtq => "GTQ", 320, 2;
+    Gyd => "GYD", 328, 2;
+    Hkd => "HKD", 344, 2, "HK$";
+    Hnl => "HNL", 340, 2;
+    Htg => "HTG", 332, 2;
+    Huf => "HUF", 348, 2;
+    Idr => "IDR", 360, 2;
+    Ils => "ILS", 376, 2, "₪";
+    Inr => "INR", 356, 2, "₹";
+    Iqd => "IQD", 368, 3;
+    Irr => "IRR", 364, 2;
+    Isk => "ISK", 352, 0;
+    Jmd => "JMD", 388, 2;
+    Jod => "JOD", 400, 3;
+    Jpy => "JPY", 392, 0, "¥";
+    Kes => "KES", 404, 2;
+    Kgs => "KGS", 417, 2;
+    Khr => "KHR", 116, 2;
+    Kmf => "KMF", 174, 0;
+    Kpw => "KPW", 408, 2;
+    Krw => "KRW", 410, 0, "₩";
+    Kwd => "KWD", 414, 3;
+    Kyd => "KYD", 136, 2;
+    Kzt => "KZT", 398, 2;
+    Lak => "LAK", 418, 2;
+    Lbp => "LBP", 422, 2;
+    Lkr => "LKR", 144, 2;
+    Lrd => "LRD", 430, 2;
+    Lsl => "LSL", 426, 2;
+    Lyd => "LYD", 434, 3;
+    Mad => "MAD", 504, 2;
+    Mdl => "MDL", 498, 2;
+    Mga => "MGA", 969, 2;
+    Mkd => "MKD", 807, 2;
+    Mmk => "MMK", 104, 2;
+    Mnt => "MNT", 496, 2;
+    Mop => "MOP", 446, 2;
+    Mru => "MRU", 929, 2;
+    Mur => "MUR", 480, 2;
+    Mvr => "MVR", 462, 2;
+    Mwk => "MWK", 454, 2;
+    Mxn => "MXN", 484, 2, "MX$";
+    Mxv => "MXV", 979, 2;
+    Myr => "MYR", 458, 2;
+    Mzn => "MZN", 943, 2;
+    Nad => "NAD", 516, 2;
+    Ngn => "NGN", 566, 2;
+    Nio => "NIO", 558, 2;
+    Nok => "NOK", 578, 2;
+    Npr => "NPR", 524, 2;
+    Nzd => "NZD", 554, 2, "NZ$";
+    Omr => "OMR", 512, 3;
+    Pab => "PAB", 590, 2;
+    Pen => "PEN", 604, 2;
+    Pgk => "PGK", 598, 2;
+    Php => "PHP", 608, 2;
+    Pkr => "PKR", 586, 2;
+    Pln => "PLN", 985, 2;
+    Pyg => "PYG", 600, 0;
+    Qar => "QAR", 634, 2;
+    Ron => "RON", 946, 2;
+    Rsd => "RSD", 941, 2;
+    Rub => "RUB", 643, 2;
+    Rwf => "RWF", 646, 0;
+    Sar => "SAR", 682, 2;
+    Sbd => "SBD", 90, 2;
+    Scr => "SCR", 690, 2;
+    Sdg => "SDG", 938, 2;
+    Sek => "SEK", 752, 2;
+    Sgd => "SGD", 702, 2;
+    Shp => "SHP", 654, 2;
+    Sle => "SLE", 925, 2;
+    Sos => "SOS", 706, 2;
+    Srd => "SRD", 968, 2;
+    Ssp => "SSP", 728, 2;
+    Stn => "STN", 930, 2;
+    Svc => "SVC", 222, 2;
+    Syp => "SYP", 760, 2;
+    Szl => "SZL", 748, 2;
+    Thb => "THB", 764, 2;
+    Tjs => "TJS", 972, 2;
+    Tmt => "TMT", 934, 2;
+    Tnd => "TND", 788, 3;
+    Top => "TOP", 776, 2;
+    Try => "TRY", 949, 2;
+    Ttd => "TTD", 780, 2;
+    Twd => "TWD", 901, 2;
+    Tzs => "TZS", 834, 2;
+    Uah => "UAH", 980, 2;
+    Ugx => "UGX", 800, 0;
+    Usd => "USD", 840, 2, "$";
+    Usn => "USN", 997, 2;
+    Uyi => "UYI", 940, 0;
+    Uyu => "UYU", 858, 2;
+    Uyw => "UYW", 927, 4;
+    Uzs => "UZS", 860, 2;
+    Ved => "VED", 926, 2;
+    Ves => "VES", 928, 2;
+    Vnd => "VND", 704, 0, "₫";
+    Vuv => "VUV", 548, 0;
+    Wst => "WST", 882, 2;
+    Xaf => "XAF", 950, 0;
+    Xcd => "XCD", 951, 2;
+    Xcg => "XCG", 532, 2;
+    Xof => "XOF", 952, 0;
+    Xpf => "XPF", 953, 0;
+    Yer => "YER", 886, 2;
+    Zar => "ZAR", 710, 2;
+    Zmw => "ZMW", 967, 2;
+    Zwg => "ZWG", 924, 2;
+}
+
+impl Currency {
+    /// Look a currency up by its numeric code.
+    pub fn from_numeric_code(numeric: u16) -> Option<Self> {
+        Self::ALL.iter().copied().find(|c| c.numeric_code() == numeric)
+    }
+
+    /// The currency's symbol, or its code for currencies without a well-known one.
+    pub fn symbol(&self) -> &'static str {
+        self.own_symbol().unwrap_or(self.code())
+    }
+
+    /// Format an amount of minor units with this currency's exponent and symbol,
+    /// e.g. `-£1234.50`, `¥1500` or `KWD 1.250`.
+    pub fn format_minor(&self, amount: i64) -> String {
+        let sign = if amount < 0 { "-" } else { "" };
+
+        // unsigned_abs so that i64::MIN doesn't overflow.
+        let magnitude = amount.unsigned_abs();
+        let divisor = 10_u64.pow(self.exponent());
+
+        let prefix = match self.own_symbol() {
+            Some(symbol) => symbol.to_string(),
+            None => format!("{} ", self.code()),
+        };
+
+        match self.exponent() {
+            0 => format!("{sign}{prefix}{magnitude}"),
+            digits => format!(
+                "{sign}{prefix}{}.{:0width$}",
+                magnitude / divisor,
+                magnitude % divisor,
+                width = digits as usize
+            ),
+        }
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    /// Parse an upper-case alphabetic code such as `GBP`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|c| c.code() == s)
+            .ok_or_else(|| MoneyError::UnsupportedCurrency(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_well_formed() {
+        let codes: HashSet<_> = Currency::ALL.iter().map(Currency::code).collect();
+        let numerics: HashSet<_> = Currency::ALL.iter().map(Currency::numeric_code).collect();
+
+        assert_eq!(codes.len(), Currency::ALL.len());
+        assert_eq!(numerics.len(), Currency::ALL.len());
+
+        for currency in Currency::ALL {
+            assert!(currency.code().len() == 3 && currency.code().bytes().all(|b| b.is_ascii_uppercase()));
+            assert!((1..1000).contains(&currency.numeric_code()));
+
+            // The serde name and the code must agree, or stored events won't round trip.
+            assert_eq!(serde_json::to_string(currency).unwrap(), format!("\"{}\"", currency.code()));
+            assert_eq!(currency.code().parse::<Currency>().unwrap(), *currency);
+        }
+    }
+
+    #[test]
+    fn exponents_follow_iso_4217() {
+        assert_eq!(Currency::Gbp.exponent(), 2);
+        assert_eq!(Currency::Jpy.exponent(), 0);
+        assert_eq!(Currency::Kwd.exponent(), 3);
+        assert_eq!(Currency::Bhd.exponent(), 3);
+        assert_eq!(Currency::Clf.exponent(), 4);
+    }
+
+    #[test]
+    fn looks_up_by_numeric_code() {
+        assert_eq!(Currency::from_numeric_code(826), Some(Currency::Gbp));
+        assert_eq!(Currency::from_numeric_code(8), Some(Currency::All));
+        assert_eq!(Currency::from_numeric_code(999), None);
+    }
+
+    #[test]
+    fn formats_minor_units_with_the_currency_exponent() {
+        assert_eq!(Currency::Jpy.format_minor(1500), "¥1500");
+        assert_eq!(Currency::Kwd.format_minor(1_250), "KWD 1.250");
+        assert_eq!(Currency::Clf.format_minor(5), "CLF 0.0005");
+        assert_eq!(Currency::Gbp.format_minor(-123_450), "-£1234.50");
+        assert_eq!(Currency::Gbp.format_minor(-5), "-£0.05");
+    }
+
+    #[test]
+    fn formats_extreme_amounts() {
+        assert_eq!(Currency::Usd.format_minor(i64::MAX), "$92233720368547758.07");
+        assert_eq!(Currency::Usd.format_minor(i64::MIN), "-$92233720368547758.08");
+        assert_eq!(Currency::Jpy.format_minor(i64::MIN), "-¥9223372036854775808");
+    }
+}
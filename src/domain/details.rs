@@ -0,0 +1,105 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Descriptive details about an account. None of them affect its balance.
+///
+/// Fields are left out of the serialized form when unset, so an opening event
+/// without details serializes exactly as it did before details existed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDetails {
+    /// Who the account belongs to, in the caller's own terms, e.g. a customer number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub labels: BTreeSet<String>,
+    /// The account's id in another system. Unique across the ledger.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// The account's code in the chart of accounts, e.g. `4000`. Unique across the ledger,
+    /// and fixed once the account is opened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Code of the account this one rolls up into. It must be of the same type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_code: Option<String>,
+}
+
+impl AccountDetails {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply `changes` on top of these details. An empty string clears a field.
+    pub fn apply(&mut self, changes: &AccountDetailsChanges) {
+        let replace = |field: &mut Option<String>, change: &Option<String>| {
+            if let Some(value) = change {
+                *field = Some(value.clone()).filter(|v| !v.is_empty());
+            }
+        };
+
+        replace(&mut self.owner_ref, &changes.owner_ref);
+        replace(&mut self.display_name, &changes.display_name);
+        replace(&mut self.external_id, &changes.external_id);
+
+        if let Some(labels) = &changes.labels {
+            self.labels = labels.clone();
+        }
+    }
+}
+
+/// A partial update to [`AccountDetails`]. Fields left as `None` are unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDetailsChanges {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Replaces every label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+}
+
+impl AccountDetailsChanges {
+    /// The external id these changes would leave the account with, if they change it.
+    pub fn new_external_id(&self) -> Option<&str> {
+        self.external_id.as_deref().filter(|id| !id.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_replace_only_the_fields_they_set() {
+        let mut details = AccountDetails {
+            owner_ref: Some("cust-42".to_string()),
+            display_name: Some("Savings".to_string()),
+            labels: BTreeSet::from(["retail".to_string()]),
+            external_id: Some("ext-1".to_string()),
+            ..Default::default()
+        };
+
+        details.apply(&AccountDetailsChanges {
+            display_name: Some("Rainy day".to_string()),
+            labels: Some(BTreeSet::new()),
+            external_id: Some(String::new()),
+            ..Default::default()
+        });
+
+        assert_eq!(details.owner_ref.as_deref(), Some("cust-42"));
+        assert_eq!(details.display_name.as_deref(), Some("Rainy day"));
+        assert!(details.labels.is_empty());
+        assert_eq!(details.external_id, None);
+    }
+
+    #[test]
+    fn empty_details_serialize_to_nothing() {
+        assert_eq!(serde_json::to_string(&AccountDetails::default()).unwrap(), "{}");
+    }
+}
@@ -1,12 +1,19 @@
 use thiserror::Error;
+use time::Date;
 
-use crate::domain::MoneyError;
+use crate::{domain::{Currency, MoneyError, balances::Balances, chart::AccountType, fx::FxError, types::{AccountId, EventId}}, store::{StoreError, StreamVersion}};
 
 #[derive(Debug, Error)]
 pub enum DomainError {
     #[error("account not found")]
     AccountNotFound,
 
+    #[error("event not found")]
+    EventNotFound,
+
+    #[error("cannot transfer from an account to itself")]
+    SelfTransfer,
+
     #[error("invalid money value: {0}")]
     InvalidMoney(#[from] MoneyError),
 
@@ -17,4 +24,98 @@ pub enum DomainError {
         required_minor: i64,
         available_minor: i64,
     },
+
+    #[error("account is frozen")]
+    AccountFrozen,
+
+    #[error("account is not frozen")]
+    AccountNotFrozen,
+
+    #[error("account is closed")]
+    AccountClosed,
+
+    #[error("account can only be closed with a zero balance and no open holds")]
+    AccountNotSettled,
+
+    #[error("external id {0} is already used by another account")]
+    ExternalIdTaken(String),
+
+    #[error("system accounts only move through journal entries")]
+    SystemAccount,
+
+    #[error("{0} accounts only move through journal entries")]
+    JournalOnly(AccountType),
+
+    #[error("account code {0} is already used by another account")]
+    AccountCodeTaken(String),
+
+    #[error("invalid parent account: {0}")]
+    InvalidParent(String),
+
+    #[error("invalid journal entry: {0}")]
+    InvalidEntry(String),
+
+    #[error("journal entry does not balance in {currency:?}: debits {debits_minor}, credits {credits_minor} (minor units)")]
+    UnbalancedEntry {
+        currency: Currency,
+        debits_minor: i64,
+        credits_minor: i64,
+    },
+
+    #[error("value date {value_date} is outside {earliest} to {latest}")]
+    ValueDateOutOfRange {
+        value_date: Date,
+        earliest: Date,
+        latest: Date,
+    },
+
+    #[error("schedule not found")]
+    ScheduleNotFound,
+
+    #[error("schedule is paused")]
+    SchedulePaused,
+
+    #[error("schedule is not paused")]
+    ScheduleNotPaused,
+
+    #[error("schedule is cancelled")]
+    ScheduleCancelled,
+
+    #[error("invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    #[error("hold not found")]
+    HoldNotFound,
+
+    #[error("cannot reverse: {0}")]
+    NotReversible(String),
+
+    #[error("already reversed by event {0}")]
+    AlreadyReversed(EventId),
+
+    #[error("cannot capture {requested_minor} (minor units) against a hold of {held_minor}")]
+    CaptureExceedsHold {
+        held_minor: i64,
+        requested_minor: i64,
+    },
+
+    #[error("currency conversion failed: {0}")]
+    Fx(#[from] FxError),
+
+    #[error("account {account_id} was modified concurrently: expected version {expected}, found {actual}")]
+    ConcurrencyConflict {
+        account_id: AccountId,
+        expected: StreamVersion,
+        actual: StreamVersion,
+    },
+
+    #[error("snapshot for account {account_id} says {from_snapshot} but a full replay gives {replayed}")]
+    SnapshotMismatch {
+        account_id: AccountId,
+        from_snapshot: Box<Balances>,
+        replayed: Box<Balances>,
+    },
+
+    #[error("event store failure: {0}")]
+    Storage(#[from] StoreError),
 }
\ No newline at end of file
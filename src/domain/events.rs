@@ -1,17 +1,126 @@
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime, UtcOffset};
 
-use crate::domain::{Money, types::{AccountId, EventId}};
+use crate::domain::{Currency, Money, chain::EventHash, chart::AccountType, details::{AccountDetails, AccountDetailsChanges}, fx::{Conversion, Rate}, journal::{JournalEntry, JournalLeg}, schedule::{Frequency, ScheduledPayment}, types::{AccountId, ConversionId, EntryId, EventId, HoldId, ScheduleId, TransferId}};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LedgerEventPayload {
-    // A new account was opened
-    AccountOpened,
-    // Add money to account
-    Deposit { amount: Money },
-    // Remove money from account
-    Withdraw { amount: Money },
+    // A new account was opened, holding a single currency. Accounts opened before
+    // currencies were recorded have none and hold GBP. The field is left out of the
+    // serialized form when absent so those events keep their original hashes, and
+    // the same goes for the account type and details.
+    AccountOpened {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        currency: Option<Currency>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        account_type: Option<AccountType>,
+        #[serde(default, skip_serializing_if = "AccountDetails::is_empty")]
+        details: AccountDetails,
+    },
+    // Some of the account's details were changed
+    AccountDetailsUpdated { changes: AccountDetailsChanges },
+    // Add money to account. When `via` is set the same event is recorded on that system
+    // account as the other side of the entry; older deposits have no other side. A
+    // `value_date` says the money counts from that day rather than from when it was booked
+    Deposit {
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via: Option<AccountId>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_date: Option<Date>,
+    },
+    // Remove money from account, recorded on `via` and value dated in the same way as a deposit
+    Withdrawal {
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via: Option<AccountId>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_date: Option<Date>,
+    },
+    // Funds reserved for a later capture. They stay in the balance but are no longer available
+    HoldAuthorized { hold_id: HoldId, amount: Money },
+    // Some or all of a hold taken from the balance. Anything not captured is released
+    HoldCaptured {
+        hold_id: HoldId,
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via: Option<AccountId>,
+    },
+    // A hold released without taking anything
+    HoldVoided { hold_id: HoldId },
+    // A hold released because it went uncaptured for too long
+    HoldExpired { hold_id: HoldId },
+    // No more debits, and no more credits either unless `allow_credits` is set
+    AccountFrozen { allow_credits: bool },
+    // Debits and credits allowed again
+    AccountUnfrozen,
+    // Closed for good, at a zero balance
+    AccountClosed,
+    // Arranged overdraft in the limit's currency, replacing any earlier one. Zero removes it
+    OverdraftLimitSet { limit: Money },
+    // Money sent to another account, paired with a TransferCredit on the counterparty.
+    // Only transfers made late by a standing order carry a `value_date`
+    TransferDebit {
+        transfer_id: TransferId,
+        amount: Money,
+        counterparty: AccountId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_date: Option<Date>,
+    },
+    // Money received from another account, paired with a TransferDebit on the counterparty
+    TransferCredit {
+        transfer_id: TransferId,
+        amount: Money,
+        counterparty: AccountId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_date: Option<Date>,
+    },
+    // `source` was taken from `from` and exchanged at `rate` for `target`, paid into `to`.
+    // Recorded once on each account involved, including `via`, which takes the source
+    // and pays out the target
+    CurrencyConverted {
+        conversion_id: ConversionId,
+        from: AccountId,
+        to: AccountId,
+        source: Money,
+        target: Money,
+        rate: Rate,
+        rate_source: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via: Option<AccountId>,
+    },
+    // A balanced set of debits and credits, recorded once on each account it touches
+    JournalEntryPosted {
+        entry_id: EntryId,
+        legs: Vec<JournalLeg>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<String>,
+    },
+    // Undoes an earlier money movement with its legs the other way round. Recorded once on
+    // each account the movement touched, each naming the original event on that account
+    EventReversed {
+        reverses: EventId,
+        reason: String,
+        legs: Vec<JournalLeg>,
+    },
+    // A standing order taking `payment` from the account every `frequency`, first at `starts_at`
+    PaymentScheduled {
+        schedule_id: ScheduleId,
+        payment: ScheduledPayment,
+        frequency: Frequency,
+        starts_at: OffsetDateTime,
+    },
+    // No runs until resumed. Those falling due meanwhile are skipped
+    SchedulePaused { schedule_id: ScheduleId },
+    // Runs are made again
+    ScheduleResumed { schedule_id: ScheduleId },
+    // No more runs, for good
+    ScheduleCancelled { schedule_id: ScheduleId },
+    // The run due at `due_at` was made by `payment_id`, appended together with it
+    ScheduledPaymentMade { schedule_id: ScheduleId, due_at: OffsetDateTime, payment_id: EventId },
+    // The run due at `due_at` couldn't be made, and won't be retried
+    ScheduledPaymentFailed { schedule_id: ScheduleId, due_at: OffsetDateTime, reason: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,34 +128,286 @@ pub struct LedgerEvent {
     pub id: EventId,
     pub account_id: AccountId,
     pub created_at: OffsetDateTime,
-    pub payload: LedgerEventPayload
+    pub payload: LedgerEventPayload,
+    /// Hash of the event before this one in the global log. Set by the store on append.
+    pub prev_hash: EventHash,
+    /// Hash over this event's fields and `prev_hash`. Set by the store on append.
+    pub hash: EventHash,
 }
 
 impl LedgerEvent {
-    pub fn account_opened(account_id: AccountId)  -> Self {
+    /// A new, not yet sealed event on `account_id`, stamped with the current time.
+    fn new(account_id: AccountId, payload: LedgerEventPayload) -> Self {
         Self {
             id: EventId::new_v4(),
             created_at: OffsetDateTime::now_utc(),
             account_id,
-            payload: LedgerEventPayload::AccountOpened
+            payload,
+            prev_hash: EventHash::ZERO,
+            hash: EventHash::ZERO,
         }
     }
 
-    pub fn deposit(account_id: AccountId, amount: Money)  -> Self {
-        Self {
+    pub fn account_opened(account_id: AccountId, currency: Currency) -> Self {
+        Self::account_opened_with_details(account_id, AccountType::Liability, currency, AccountDetails::default())
+    }
+
+    pub fn account_opened_with_details(account_id: AccountId, account_type: AccountType, currency: Currency, details: AccountDetails) -> Self {
+        Self::new(account_id, LedgerEventPayload::AccountOpened { currency: Some(currency), account_type: Some(account_type), details })
+    }
+
+    pub fn account_details_updated(account_id: AccountId, changes: AccountDetailsChanges) -> Self {
+        Self::new(account_id, LedgerEventPayload::AccountDetailsUpdated { changes })
+    }
+
+    /// A deposit with no other side, as recorded before double entry.
+    pub fn deposit(account_id: AccountId, amount: Money) -> Self {
+        Self::new(account_id, LedgerEventPayload::Deposit { amount, via: None, value_date: None })
+    }
+
+    /// A withdrawal with no other side, as recorded before double entry.
+    pub fn withdraw(account_id: AccountId, amount: Money) -> Self {
+        Self::new(account_id, LedgerEventPayload::Withdrawal { amount, via: None, value_date: None })
+    }
+
+    /// A deposit into `account_id`, recorded on it and on the system account `via`.
+    pub fn deposit_via(account_id: AccountId, via: AccountId, amount: Money, value_date: Option<Date>) -> Vec<Self> {
+        Self::recorded_on([account_id, via], LedgerEventPayload::Deposit { amount, via: Some(via), value_date })
+    }
+
+    /// A withdrawal from `account_id`, recorded on it and on the system account `via`.
+    pub fn withdraw_via(account_id: AccountId, via: AccountId, amount: Money, value_date: Option<Date>) -> Vec<Self> {
+        Self::recorded_on([account_id, via], LedgerEventPayload::Withdrawal { amount, via: Some(via), value_date })
+    }
+
+    pub fn overdraft_limit_set(account_id: AccountId, limit: Money) -> Self {
+        Self::new(account_id, LedgerEventPayload::OverdraftLimitSet { limit })
+    }
+
+    pub fn account_frozen(account_id: AccountId, allow_credits: bool) -> Self {
+        Self::new(account_id, LedgerEventPayload::AccountFrozen { allow_credits })
+    }
+
+    pub fn account_unfrozen(account_id: AccountId) -> Self {
+        Self::new(account_id, LedgerEventPayload::AccountUnfrozen)
+    }
+
+    pub fn account_closed(account_id: AccountId) -> Self {
+        Self::new(account_id, LedgerEventPayload::AccountClosed)
+    }
+
+    pub fn hold_authorized(account_id: AccountId, hold_id: HoldId, amount: Money) -> Self {
+        Self::new(account_id, LedgerEventPayload::HoldAuthorized { hold_id, amount })
+    }
+
+    /// A capture from the hold on `account_id`, recorded on it and on the system account `via`.
+    pub fn hold_captured(account_id: AccountId, via: AccountId, hold_id: HoldId, amount: Money) -> Vec<Self> {
+        Self::recorded_on([account_id, via], LedgerEventPayload::HoldCaptured { hold_id, amount, via: Some(via) })
+    }
+
+    pub fn hold_voided(account_id: AccountId, hold_id: HoldId) -> Self {
+        Self::new(account_id, LedgerEventPayload::HoldVoided { hold_id })
+    }
+
+    pub fn hold_expired(account_id: AccountId, hold_id: HoldId) -> Self {
+        Self::new(account_id, LedgerEventPayload::HoldExpired { hold_id })
+    }
+
+    /// The two legs of a transfer, sharing a transfer id and value date and pointing at
+    /// each other's account.
+    pub fn transfer(transfer_id: TransferId, from: AccountId, to: AccountId, amount: Money, value_date: Option<Date>) -> (Self, Self) {
+        let created_at = OffsetDateTime::now_utc();
+
+        let debit = Self {
             id: EventId::new_v4(),
-            created_at: OffsetDateTime::now_utc(),
-            account_id,
-            payload: LedgerEventPayload::Deposit { amount }
+            created_at,
+            account_id: from,
+            payload: LedgerEventPayload::TransferDebit { transfer_id, amount, counterparty: to, value_date },
+            prev_hash: EventHash::ZERO,
+            hash: EventHash::ZERO,
+        };
+
+        let credit = Self {
+            id: EventId::new_v4(),
+            created_at,
+            account_id: to,
+            payload: LedgerEventPayload::TransferCredit { transfer_id, amount, counterparty: from, value_date },
+            prev_hash: EventHash::ZERO,
+            hash: EventHash::ZERO,
+        };
+
+        (debit, credit)
+    }
+
+    /// The events recording a conversion: one per account involved, all with the same payload.
+    pub fn currency_converted(from: AccountId, to: AccountId, via: AccountId, conversion: &Conversion) -> Vec<Self> {
+        let payload = LedgerEventPayload::CurrencyConverted {
+            conversion_id: conversion.conversion_id,
+            from,
+            to,
+            source: conversion.source,
+            target: conversion.target,
+            rate: conversion.rate,
+            rate_source: conversion.rate_source.clone(),
+            via: Some(via),
+        };
+
+        let accounts = if from == to { vec![from, via] } else { vec![from, to, via] };
+
+        Self::recorded_on(accounts, payload)
+    }
+
+    /// The events recording a journal entry: one per account it touches, all with the same payload.
+    pub fn journal_entry_posted(entry: &JournalEntry) -> Vec<Self> {
+        let payload = LedgerEventPayload::JournalEntryPosted {
+            entry_id: entry.entry_id,
+            legs: entry.legs.clone(),
+            memo: entry.memo.clone(),
+        };
+
+        Self::recorded_on(entry.accounts(), payload)
+    }
+
+    /// The events undoing a money movement with `legs`: one per account it touched, each
+    /// naming the original event on that account.
+    pub fn event_reversed(originals: &[(AccountId, EventId)], reason: &str, legs: &[JournalLeg]) -> Vec<Self> {
+        let created_at = OffsetDateTime::now_utc();
+
+        originals
+            .iter()
+            .map(|(account_id, reverses)| Self {
+                id: EventId::new_v4(),
+                created_at,
+                account_id: *account_id,
+                payload: LedgerEventPayload::EventReversed { reverses: *reverses, reason: reason.to_string(), legs: legs.to_vec() },
+                prev_hash: EventHash::ZERO,
+                hash: EventHash::ZERO,
+            })
+            .collect()
+    }
+
+    pub fn payment_scheduled(account_id: AccountId, schedule_id: ScheduleId, payment: ScheduledPayment, frequency: Frequency, starts_at: OffsetDateTime) -> Self {
+        Self::new(account_id, LedgerEventPayload::PaymentScheduled { schedule_id, payment, frequency, starts_at })
+    }
+
+    pub fn schedule_paused(account_id: AccountId, schedule_id: ScheduleId) -> Self {
+        Self::new(account_id, LedgerEventPayload::SchedulePaused { schedule_id })
+    }
+
+    pub fn schedule_resumed(account_id: AccountId, schedule_id: ScheduleId) -> Self {
+        Self::new(account_id, LedgerEventPayload::ScheduleResumed { schedule_id })
+    }
+
+    pub fn schedule_cancelled(account_id: AccountId, schedule_id: ScheduleId) -> Self {
+        Self::new(account_id, LedgerEventPayload::ScheduleCancelled { schedule_id })
+    }
+
+    pub fn scheduled_payment_made(account_id: AccountId, schedule_id: ScheduleId, due_at: OffsetDateTime, payment_id: EventId) -> Self {
+        Self::new(account_id, LedgerEventPayload::ScheduledPaymentMade { schedule_id, due_at, payment_id })
+    }
+
+    pub fn scheduled_payment_failed(account_id: AccountId, schedule_id: ScheduleId, due_at: OffsetDateTime, reason: &str) -> Self {
+        Self::new(account_id, LedgerEventPayload::ScheduledPaymentFailed { schedule_id, due_at, reason: reason.to_string() })
+    }
+
+    /// The debits and credits of the money movement this event records, or `None` if it
+    /// moves no money. A system account's copy of a deposit, withdrawal or capture doesn't
+    /// name the customer's account, so has none either, and nor does a reversal.
+    pub fn legs(&self) -> Option<Vec<JournalLeg>> {
+        let account_id = self.account_id;
+
+        // The leg on this account, and the opposite one on `via` if it was recorded there.
+        let with_via = |leg: JournalLeg, via: Option<AccountId>| {
+            let mut legs = vec![leg];
+            legs.extend(via.map(|via| JournalLeg { account_id: via, side: leg.side.opposite(), amount: leg.amount }));
+            legs
+        };
+
+        match &self.payload {
+            LedgerEventPayload::Deposit { amount, via, .. } if *via != Some(account_id) => {
+                Some(with_via(JournalLeg::credit(account_id, *amount), *via))
+            }
+            LedgerEventPayload::Withdrawal { amount, via, .. } | LedgerEventPayload::HoldCaptured { amount, via, .. } if *via != Some(account_id) => {
+                Some(with_via(JournalLeg::debit(account_id, *amount), *via))
+            }
+            LedgerEventPayload::TransferDebit { amount, counterparty, .. } => {
+                Some(vec![JournalLeg::debit(account_id, *amount), JournalLeg::credit(*counterparty, *amount)])
+            }
+            LedgerEventPayload::TransferCredit { amount, counterparty, .. } => {
+                Some(vec![JournalLeg::debit(*counterparty, *amount), JournalLeg::credit(account_id, *amount)])
+            }
+            LedgerEventPayload::CurrencyConverted { from, to, source, target, via, .. } => match via {
+                Some(via) => Some(vec![
+                    JournalLeg::debit(*from, *source),
+                    JournalLeg::credit(*via, *source),
+                    JournalLeg::debit(*via, *target),
+                    JournalLeg::credit(*to, *target),
+                ]),
+                None => Some(vec![JournalLeg::debit(*from, *source), JournalLeg::credit(*to, *target)]),
+            },
+            LedgerEventPayload::JournalEntryPosted { legs, .. } => Some(legs.clone()),
+            _ => None,
         }
     }
 
-    pub fn withdraw(account_id: AccountId, amount: Money)  -> Self {
-        Self {
-            id: EventId::new_v4(),
-            created_at: OffsetDateTime::now_utc(),
-            account_id,
-            payload: LedgerEventPayload::Withdraw { amount }
+    /// The day the event counts from: its value date if it was given one, otherwise the
+    /// day it was booked.
+    pub fn value_date(&self) -> Date {
+        match self.payload {
+            LedgerEventPayload::Deposit { value_date: Some(value_date), .. }
+            | LedgerEventPayload::Withdrawal { value_date: Some(value_date), .. }
+            | LedgerEventPayload::TransferDebit { value_date: Some(value_date), .. }
+            | LedgerEventPayload::TransferCredit { value_date: Some(value_date), .. } => value_date,
+            _ => self.created_at.to_offset(UtcOffset::UTC).date(),
+        }
+    }
+
+    /// The instant the event counts from: the start of its value date if it was given one,
+    /// otherwise when it was booked.
+    pub fn effective_at(&self) -> OffsetDateTime {
+        match self.payload {
+            LedgerEventPayload::Deposit { value_date: Some(value_date), .. }
+            | LedgerEventPayload::Withdrawal { value_date: Some(value_date), .. }
+            | LedgerEventPayload::TransferDebit { value_date: Some(value_date), .. }
+            | LedgerEventPayload::TransferCredit { value_date: Some(value_date), .. } => value_date.midnight().assume_utc(),
+            _ => self.created_at,
+        }
+    }
+
+    /// Whether `other` records the same movement as this event, on another account.
+    pub fn same_movement(&self, other: &LedgerEvent) -> bool {
+        if self.created_at != other.created_at {
+            return false
+        }
+
+        // The two legs of a transfer differ, everything else is recorded the same on every account.
+        match (self.transfer_id(), other.transfer_id()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.payload == other.payload,
+        }
+    }
+
+    fn transfer_id(&self) -> Option<TransferId> {
+        match self.payload {
+            LedgerEventPayload::TransferDebit { transfer_id, .. } | LedgerEventPayload::TransferCredit { transfer_id, .. } => Some(transfer_id),
+            _ => None,
         }
     }
+
+    /// One event with `payload` on each of `accounts`, all booked at the same instant.
+    fn recorded_on(accounts: impl IntoIterator<Item = AccountId>, payload: LedgerEventPayload) -> Vec<Self> {
+        let created_at = OffsetDateTime::now_utc();
+
+        accounts
+            .into_iter()
+            .map(|account_id| Self {
+                id: EventId::new_v4(),
+                created_at,
+                account_id,
+                payload: payload.clone(),
+                prev_hash: EventHash::ZERO,
+                hash: EventHash::ZERO,
+            })
+            .collect()
+    }
 }
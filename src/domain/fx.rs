@@ -0,0 +1,298 @@
+//! Currency conversion at rates from a fixed table.
+//!
+//! Rates are exact decimals, never floats. A conversion multiplies the source
+//! amount by the rate and rounds the result **down** to whole minor units of the
+//! target currency, so it can never create value. The only value lost is that
+//! rounding remainder, which is always less than one minor unit of the target. A
+//! conversion that would round down to nothing is refused rather than made.
+
+use core::fmt;
+use std::{collections::HashMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::{Currency, Money, types::ConversionId};
+
+/// Most decimal places a rate may have.
+const MAX_RATE_SCALE: u32 = 12;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FxError {
+    #[error("no rate from {0:?} to {1:?}")]
+    NoRate(Currency, Currency),
+
+    #[error("invalid rate: {0}")]
+    InvalidRate(String),
+
+    #[error("invalid rate table: {0}")]
+    InvalidRateTable(String),
+
+    #[error("converted amount is too large")]
+    Overflow,
+
+    #[error("converted amount rounds down to nothing")]
+    TooSmall,
+}
+
+/// A positive exchange rate, stored as `mantissa / 10^scale`.
+///
+/// Serialized as a decimal string, keeping the precision it was written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rate {
+    mantissa: u64,
+    scale: u32,
+}
+
+impl Rate {
+    /// Convert `source` into `target`, rounding down to whole minor units. Errs with
+    /// [`FxError::TooSmall`] if that leaves nothing.
+    ///
+    /// Rates are quoted in major units, so the two currencies' exponents are
+    /// applied on top: 10.00 GBP at 190.25 is 1902 JPY, not 190250.
+    pub fn convert(&self, source: Money, target: Currency) -> Result<Money, FxError> {
+        let exact = u128::from(source.amount().unsigned_abs())
+            .checked_mul(u128::from(self.mantissa))
+            .and_then(|n| n.checked_mul(10_u128.pow(target.exponent())))
+            .ok_or(FxError::Overflow)?;
+        let converted = exact / 10_u128.pow(self.scale + source.currency().exponent());
+        if converted == 0 {
+            return Err(FxError::TooSmall)
+        }
+
+        let amount = i64::try_from(converted).map_err(|_| FxError::Overflow)?;
+
+        Money::new_minor(amount, target).map_err(|_| FxError::Overflow)
+    }
+}
+
+impl FromStr for Rate {
+    type Err = FxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FxError::InvalidRate(s.to_string());
+
+        let (whole, fraction) = match s.split_once('.') {
+            Some((_, "")) => return Err(invalid()),
+            Some(parts) => parts,
+            None => (s, ""),
+        };
+
+        if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let scale = u32::try_from(fraction.len()).map_err(|_| invalid())?;
+        if scale > MAX_RATE_SCALE {
+            return Err(invalid());
+        }
+
+        let mantissa: u64 = format!("{whole}{fraction}").parse().map_err(|_| invalid())?;
+        if mantissa == 0 {
+            return Err(invalid());
+        }
+
+        Ok(Self { mantissa, scale })
+    }
+}
+
+impl TryFrom<String> for Rate {
+    type Error = FxError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Rate> for String {
+    fn from(rate: Rate) -> Self {
+        rate.to_string()
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = format!("{:0>width$}", self.mantissa, width = self.scale as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+
+        if fraction.is_empty() {
+            write!(f, "{whole}")
+        } else {
+            write!(f, "{whole}.{fraction}")
+        }
+    }
+}
+
+/// A completed conversion, as recorded on the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversion {
+    pub conversion_id: ConversionId,
+    pub source: Money,
+    pub target: Money,
+    pub rate: Rate,
+    pub rate_source: String,
+}
+
+#[derive(Deserialize)]
+struct RateFile {
+    source: String,
+    rates: Vec<RateEntry>,
+}
+
+#[derive(Deserialize)]
+struct RateEntry {
+    from: Currency,
+    to: Currency,
+    rate: Rate,
+}
+
+/// Exchange rates between currency pairs, all from one named source.
+///
+/// Only the pairs listed are available: the inverse of a rate is not implied, as
+/// buying and selling a currency usually happen at different rates.
+#[derive(Debug, Clone, Default)]
+pub struct RateTable {
+    source: String,
+    rates: HashMap<(Currency, Currency), Rate>,
+}
+
+impl RateTable {
+    /// Parse a rate table such as:
+    ///
+    /// ```json
+    /// { "source": "ECB 2026-10-16", "rates": [{ "from": "GBP", "to": "EUR", "rate": "1.1650" }] }
+    /// ```
+    pub fn from_json(data: &str) -> Result<Self, FxError> {
+        let file: RateFile = serde_json::from_str(data).map_err(|err| FxError::InvalidRateTable(err.to_string()))?;
+
+        let mut rates = HashMap::new();
+
+        for entry in file.rates {
+            if entry.from == entry.to {
+                return Err(FxError::InvalidRateTable(format!("rate from {:?} to itself", entry.from)));
+            }
+
+            if rates.insert((entry.from, entry.to), entry.rate).is_some() {
+                return Err(FxError::InvalidRateTable(format!("duplicate rate from {:?} to {:?}", entry.from, entry.to)));
+            }
+        }
+
+        Ok(Self { source: file.source, rates })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn rate(&self, from: Currency, to: Currency) -> Result<Rate, FxError> {
+        self.rates.get(&(from, to)).copied().ok_or(FxError::NoRate(from, to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_parses_and_displays_with_its_precision() {
+        assert_eq!("1.1650".parse::<Rate>().unwrap().to_string(), "1.1650");
+        assert_eq!("0.005".parse::<Rate>().unwrap().to_string(), "0.005");
+        assert_eq!("2".parse::<Rate>().unwrap().to_string(), "2");
+
+        for invalid in ["", ".5", "1.", "-1.2", "0.000", "1e3", "1.0000000000001"] {
+            assert!(invalid.parse::<Rate>().is_err(), "{invalid} should be rejected");
+        }
+    }
+
+    #[test]
+    fn conversion_rounds_down_to_whole_minor_units() {
+        let rate: Rate = "1.1650".parse().unwrap();
+
+        // 10.01 * 1.1650 = 11.661650
+        let target = rate.convert(Money::new_minor(10_01, Currency::Gbp).unwrap(), Currency::Eur).unwrap();
+        assert_eq!(target, Money::new_minor(11_66, Currency::Eur).unwrap());
+    }
+
+    #[test]
+    fn conversion_that_rounds_down_to_nothing_is_refused() {
+        let rate: Rate = "0.85".parse().unwrap();
+
+        // 0.01 * 0.85 = 0.0085
+        let err = rate.convert(Money::new_minor(1, Currency::Eur).unwrap(), Currency::Gbp).unwrap_err();
+        assert_eq!(err, FxError::TooSmall);
+
+        let target = rate.convert(Money::new_minor(2, Currency::Eur).unwrap(), Currency::Gbp).unwrap();
+        assert_eq!(target, Money::new_minor(1, Currency::Gbp).unwrap());
+    }
+
+    #[test]
+    fn conversion_applies_both_currency_exponents() {
+        let rate: Rate = "190.25".parse().unwrap();
+
+        // 10.01 * 190.25 = 1904.4025
+        let yen = rate.convert(Money::new_minor(10_01, Currency::Gbp).unwrap(), Currency::Jpy).unwrap();
+        assert_eq!(yen, Money::new_minor(1904, Currency::Jpy).unwrap());
+
+        // 1904 * 0.002 = 3.808
+        let rate: Rate = "0.002".parse().unwrap();
+        let dinars = rate.convert(yen, Currency::Kwd).unwrap();
+        assert_eq!(dinars, Money::new_minor(3_808, Currency::Kwd).unwrap());
+    }
+
+    #[test]
+    fn conversion_loses_less_than_one_minor_unit() {
+        let rate: Rate = "0.857321".parse().unwrap();
+
+        for amount in (0..100_000).step_by(7) {
+            // Compare in millionths of a minor unit: target <= exact < target + 1.
+            let exact = i128::from(amount) * 857_321;
+
+            let target = match rate.convert(Money::new_minor(amount, Currency::Eur).unwrap(), Currency::Gbp) {
+                Err(FxError::TooSmall) => {
+                    assert!(exact < 1_000_000, "{amount} refused as too small");
+                    continue
+                }
+                result => result.unwrap(),
+            };
+
+            let rounded = i128::from(target.amount()) * 1_000_000;
+            assert!(rounded <= exact && exact < rounded + 1_000_000, "{amount} converted to {}", target.amount());
+        }
+    }
+
+    #[test]
+    fn conversion_too_large_for_money_overflows() {
+        let rate: Rate = "3".parse().unwrap();
+
+        let err = rate.convert(Money::new_minor(i64::MAX / 2, Currency::Usd).unwrap(), Currency::Gbp).unwrap_err();
+        assert_eq!(err, FxError::Overflow);
+    }
+
+    #[test]
+    fn rate_table_only_offers_listed_pairs() {
+        let table = RateTable::from_json(r#"{
+            "source": "test desk",
+            "rates": [
+                { "from": "GBP", "to": "EUR", "rate": "1.1650" },
+                { "from": "EUR", "to": "GBP", "rate": "0.8500" }
+            ]
+        }"#).unwrap();
+
+        assert_eq!(table.source(), "test desk");
+        assert_eq!(table.rate(Currency::Gbp, Currency::Eur).unwrap().to_string(), "1.1650");
+        assert_eq!(table.rate(Currency::Usd, Currency::Gbp), Err(FxError::NoRate(Currency::Usd, Currency::Gbp)));
+    }
+
+    #[test]
+    fn rate_table_rejects_duplicates_and_bad_rates() {
+        let duplicate = r#"{ "source": "x", "rates": [
+            { "from": "GBP", "to": "EUR", "rate": "1.1" },
+            { "from": "GBP", "to": "EUR", "rate": "1.2" }
+        ] }"#;
+        assert!(matches!(RateTable::from_json(duplicate), Err(FxError::InvalidRateTable(_))));
+
+        let negative = r#"{ "source": "x", "rates": [{ "from": "GBP", "to": "EUR", "rate": "-1.1" }] }"#;
+        assert!(matches!(RateTable::from_json(negative), Err(FxError::InvalidRateTable(_))));
+    }
+}
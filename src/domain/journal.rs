@@ -0,0 +1,232 @@
+//! Double-entry bookkeeping.
+//!
+//! Every movement of money has two sides. Deposits, withdrawals, captures and
+//! conversions are booked against a [`SystemAccount`] standing for the world
+//! outside the ledger, and a [`JournalEntry`] can move money between any accounts
+//! as long as its debits and credits balance in every currency. So across all
+//! accounts, debits always equal credits, which [`TrialBalance`] shows.
+//!
+//! Whether a debit adds to an account's balance or takes from it depends on its
+//! [`AccountType`]: see [`crate::domain::chart`].
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Currency, Money, MoneyError, chart::AccountType, errors::DomainError, types::{AccountId, EntryId}};
+
+/// Accounts the ledger keeps for itself, each with a fixed id. They are opened the
+/// first time they are needed and are exempt from funds checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SystemAccount {
+    /// Counterparty to deposits, withdrawals and captured holds.
+    CashInBank,
+    /// Where entries park money that can't be placed yet.
+    Suspense,
+    /// Counterparty to both sides of every currency conversion.
+    FxPosition,
+}
+
+impl SystemAccount {
+    pub const ALL: [SystemAccount; 3] = [SystemAccount::CashInBank, SystemAccount::Suspense, SystemAccount::FxPosition];
+
+    pub fn id(&self) -> AccountId {
+        match self {
+            SystemAccount::CashInBank => AccountId::from_u128(1),
+            SystemAccount::Suspense => AccountId::from_u128(2),
+            SystemAccount::FxPosition => AccountId::from_u128(3),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SystemAccount::CashInBank => "Cash in bank",
+            SystemAccount::Suspense => "Suspense",
+            SystemAccount::FxPosition => "FX position",
+        }
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        match self {
+            SystemAccount::CashInBank | SystemAccount::FxPosition => AccountType::Asset,
+            SystemAccount::Suspense => AccountType::Liability,
+        }
+    }
+
+    pub fn from_id(account_id: AccountId) -> Option<Self> {
+        Self::ALL.into_iter().find(|system| system.id() == account_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Side {
+    Debit,
+    Credit,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Debit => Side::Credit,
+            Side::Credit => Side::Debit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalLeg {
+    pub account_id: AccountId,
+    pub side: Side,
+    pub amount: Money,
+}
+
+impl JournalLeg {
+    pub fn debit(account_id: AccountId, amount: Money) -> Self {
+        Self { account_id, side: Side::Debit, amount }
+    }
+
+    pub fn credit(account_id: AccountId, amount: Money) -> Self {
+        Self { account_id, side: Side::Credit, amount }
+    }
+}
+
+/// A balanced set of debits and credits, posted all together or not at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub entry_id: EntryId,
+    pub legs: Vec<JournalLeg>,
+    pub memo: Option<String>,
+}
+
+impl JournalEntry {
+    /// An entry with at least one debit and one credit, no zero legs, and debits
+    /// equal to credits in every currency.
+    pub fn new(legs: Vec<JournalLeg>, memo: Option<String>) -> Result<Self, DomainError> {
+        if !legs.iter().any(|leg| leg.side == Side::Debit) || !legs.iter().any(|leg| leg.side == Side::Credit) {
+            return Err(DomainError::InvalidEntry("an entry needs at least one debit and one credit".to_string()))
+        }
+
+        if legs.iter().any(|leg| leg.amount.amount() == 0) {
+            return Err(DomainError::InvalidEntry("legs must move a non-zero amount".to_string()))
+        }
+
+        for (currency, (debits, credits)) in totals(&legs)? {
+            if debits != credits {
+                return Err(DomainError::UnbalancedEntry { currency, debits_minor: debits.amount(), credits_minor: credits.amount() })
+            }
+        }
+
+        Ok(Self { entry_id: EntryId::new_v4(), legs, memo })
+    }
+
+    /// Every account the entry touches, each once, in the order they first appear.
+    pub fn accounts(&self) -> Vec<AccountId> {
+        let mut accounts = Vec::new();
+
+        for leg in &self.legs {
+            if !accounts.contains(&leg.account_id) {
+                accounts.push(leg.account_id);
+            }
+        }
+
+        accounts
+    }
+}
+
+/// Total debits and credits per currency.
+fn totals(legs: &[JournalLeg]) -> Result<BTreeMap<Currency, (Money, Money)>, MoneyError> {
+    let mut totals = BTreeMap::new();
+
+    for leg in legs {
+        let currency = leg.amount.currency();
+        let (debits, credits) = totals.entry(currency).or_insert((Money::zero(currency), Money::zero(currency)));
+
+        match leg.side {
+            Side::Debit => *debits = debits.checked_add(leg.amount)?,
+            Side::Credit => *credits = credits.checked_add(leg.amount)?,
+        }
+    }
+
+    Ok(totals)
+}
+
+/// One account's balance in one currency, in the debit column if negative and the
+/// credit column otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrialBalanceLine {
+    pub account_id: AccountId,
+    pub currency: Currency,
+    pub debit: Money,
+    pub credit: Money,
+}
+
+/// Every account's balance across the whole ledger, with the column totals per currency.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrialBalance {
+    pub lines: Vec<TrialBalanceLine>,
+    pub totals: BTreeMap<Currency, (Money, Money)>,
+}
+
+impl TrialBalance {
+    pub fn push(&mut self, line: TrialBalanceLine) -> Result<(), MoneyError> {
+        let (debits, credits) = self.totals
+            .entry(line.currency)
+            .or_insert((Money::zero(line.currency), Money::zero(line.currency)));
+
+        *debits = debits.checked_add(line.debit)?;
+        *credits = credits.checked_add(line.credit)?;
+
+        self.lines.push(line);
+
+        Ok(())
+    }
+
+    /// Whether debits equal credits in every currency.
+    pub fn is_balanced(&self) -> bool {
+        self.totals.values().all(|(debits, credits)| debits == credits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gbp(minor: i64) -> Money {
+        Money::new_minor(minor, Currency::Gbp).unwrap()
+    }
+
+    #[test]
+    fn entries_must_balance_in_every_currency() {
+        let (a, b) = (AccountId::new_v4(), AccountId::new_v4());
+
+        let entry = JournalEntry::new(vec![JournalLeg::debit(a, gbp(10_00)), JournalLeg::credit(b, gbp(6_00)), JournalLeg::credit(a, gbp(4_00))], None).unwrap();
+        assert_eq!(entry.accounts(), [a, b]);
+
+        let err = JournalEntry::new(vec![JournalLeg::debit(a, gbp(10_00)), JournalLeg::credit(b, gbp(9_99))], None).unwrap_err();
+        assert!(matches!(err, DomainError::UnbalancedEntry { currency: Currency::Gbp, debits_minor: 10_00, credits_minor: 9_99 }));
+
+        // Pounds don't balance euros.
+        let euros = Money::new_minor(10_00, Currency::Eur).unwrap();
+        let err = JournalEntry::new(vec![JournalLeg::debit(a, gbp(10_00)), JournalLeg::credit(b, euros)], None).unwrap_err();
+        assert!(matches!(err, DomainError::UnbalancedEntry { .. }));
+    }
+
+    #[test]
+    fn entries_need_both_sides_and_no_zero_legs() {
+        let (a, b) = (AccountId::new_v4(), AccountId::new_v4());
+
+        assert!(matches!(JournalEntry::new(vec![], None), Err(DomainError::InvalidEntry(_))));
+        assert!(matches!(JournalEntry::new(vec![JournalLeg::debit(a, gbp(0)), JournalLeg::credit(b, gbp(0))], None), Err(DomainError::InvalidEntry(_))));
+    }
+
+    #[test]
+    fn system_accounts_have_fixed_ids() {
+        for system in SystemAccount::ALL {
+            assert_eq!(SystemAccount::from_id(system.id()), Some(system));
+        }
+
+        assert_eq!(SystemAccount::from_id(AccountId::new_v4()), None);
+    }
+}
@@ -1,183 +1,2703 @@
-use tracing::info;
+use std::{borrow::Cow, collections::{BTreeMap, HashMap}, time::Duration};
 
-use crate::domain::{Currency, Money, errors::DomainError, events::{LedgerEvent, LedgerEventPayload}, types::{AccountId, EventId}};
+use time::{Date, OffsetDateTime, UtcOffset};
+use tracing::{info, warn};
 
-#[derive(Debug, Default)]
+use crate::{domain::{Currency, Money, MoneyError, SignedMoney, balances::Balances, chart::{AccountType, BalanceSheet, IncomeStatement, StatementLine}, details::{AccountDetails, AccountDetailsChanges}, chain::{self, ChainBreak, EventHash}, errors::DomainError, fx::{Conversion, RateTable}, journal::{JournalEntry, JournalLeg, Side, SystemAccount, TrialBalance, TrialBalanceLine}, merkle::{self, InclusionProof}, events::{LedgerEvent, LedgerEventPayload}, schedule::{Frequency, Schedule, ScheduleStatus, ScheduledPayment}, snapshot::BalanceSnapshot, status::AccountStatus, types::{AccountId, ConversionId, EntryId, EventId, HoldId, ScheduleId, TransferId}}, store::{EventStore, InMemoryEventStore, StoreError, StreamVersion}};
+
+/// Snapshot an account's balance every this many events unless configured otherwise.
+pub const DEFAULT_SNAPSHOT_INTERVAL: StreamVersion = 100;
+
+/// Expire a hold this long after it was authorized unless configured otherwise.
+pub const DEFAULT_HOLD_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The longest hold TTL that can be configured: a hundred years.
+pub const MAX_HOLD_TTL: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+
+/// How far before today a value date may go unless configured otherwise.
+pub const DEFAULT_MAX_BACKDATE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How far after today a value date may go unless configured otherwise.
+pub const DEFAULT_MAX_FORWARD_DATE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A point in an account's history to read its balance at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsOf {
+    /// Include every event booked at or before this instant.
+    Time(OffsetDateTime),
+    /// Include the first `n` events of the account's stream.
+    Version(StreamVersion),
+    /// Include every event with a value date on or before `date`, out of those booked at
+    /// or before `known_at`: the balance on that day as the ledger knew it then. Events
+    /// without a value date count from the day they were booked.
+    ValueDate { date: Date, known_at: OffsetDateTime },
+}
+
+/// An account as it stood at some point, for the financial statements.
+struct ChartAccount {
+    account_id: AccountId,
+    details: AccountDetails,
+    balances: Balances,
+}
+
+#[derive(Debug)]
 pub struct Ledger {
-    events: Vec<LedgerEvent>,
+    store: Box<dyn EventStore>,
+    snapshot_interval: StreamVersion,
+    /// Running balances per account as of the last event this ledger appended.
+    /// Streams are append-only, so a cached state never goes wrong, it can only fall behind.
+    states: HashMap<AccountId, BalanceSnapshot>,
+    rates: RateTable,
+    hold_ttl: Duration,
+    max_backdate: Duration,
+    max_forward_date: Duration,
+    /// Where the value date window and standing orders take the current time from.
+    clock: fn() -> OffsetDateTime,
+    /// Holds not yet captured, voided or expired, with their account and when they were
+    /// authorized, as of the first `indexed_position` events in the global log.
+    open_holds: HashMap<HoldId, (AccountId, OffsetDateTime)>,
+    /// Accounts by external id, as of the first `indexed_position` events in the global log.
+    external_ids: HashMap<String, AccountId>,
+    /// Account types by chart of accounts code, indexed the same way.
+    codes: HashMap<String, AccountType>,
+    /// Every standing order ever set up, indexed the same way.
+    schedules: HashMap<ScheduleId, Schedule>,
+    /// The account each event was recorded on, indexed the same way.
+    event_accounts: HashMap<EventId, AccountId>,
+    indexed_position: u64,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Ledger {
     pub fn new() -> Self {
-        Self { events: Vec::new() }
+        Self::with_store(InMemoryEventStore::new())
+    }
+
+    pub fn with_store(store: impl EventStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            states: HashMap::new(),
+            rates: RateTable::default(),
+            hold_ttl: DEFAULT_HOLD_TTL,
+            max_backdate: DEFAULT_MAX_BACKDATE,
+            max_forward_date: DEFAULT_MAX_FORWARD_DATE,
+            clock: OffsetDateTime::now_utc,
+            open_holds: HashMap::new(),
+            external_ids: HashMap::new(),
+            codes: HashMap::new(),
+            schedules: HashMap::new(),
+            event_accounts: HashMap::new(),
+            indexed_position: 0,
+        }
     }
 
-    pub fn events(&self) -> &[LedgerEvent] {
-        &self.events
+    /// Take a balance snapshot each time an account's stream version reaches a multiple of `interval`.
+    pub fn with_snapshot_interval(mut self, interval: StreamVersion) -> Self {
+        self.snapshot_interval = interval.max(1);
+        self
     }
 
-    pub fn events_for_account(&self, account_id: AccountId) -> Result<Vec<LedgerEvent>, DomainError> {
+    /// Exchange rates used by [`Ledger::convert`]. Without them no conversion is possible.
+    pub fn with_rates(mut self, rates: RateTable) -> Self {
+        self.rates = rates;
+        self
+    }
+
+    /// How long a hold may stay uncaptured before [`Ledger::expire_holds`] releases it.
+    pub fn with_hold_ttl(mut self, ttl: Duration) -> Self {
+        self.hold_ttl = ttl;
+        self
+    }
+
+    /// How far before and after today the value date of a deposit or withdrawal may be.
+    /// Both are counted in whole days.
+    pub fn with_value_date_window(mut self, max_backdate: Duration, max_forward_date: Duration) -> Self {
+        self.max_backdate = max_backdate;
+        self.max_forward_date = max_forward_date;
+        self
+    }
+
+    /// Read the current time from `clock` rather than the system clock when deciding which
+    /// value dates are allowed, which scheduled runs are late and whether a schedule starts
+    /// in the past.
+    pub fn with_clock(mut self, clock: fn() -> OffsetDateTime) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn events_for_account(&self, account_id: AccountId) -> Result<Cow<'_, [LedgerEvent]>, DomainError> {
         info!("Looking up account {}", account_id);
 
-        if !self.account_exists(account_id) {
+        let events = self.store.read_stream(account_id)?;
+
+        if events.is_empty() {
             return Err(DomainError::AccountNotFound)
         }
 
-        Ok(self.events
-            .iter()
-            .filter(|e| e.account_id == account_id)
-            .cloned()
-            .collect())
+        Ok(events)
+    }
+
+    pub fn open_account(&mut self, currency: Currency) -> Result<AccountId, DomainError> {
+        self.open_account_with_details(currency, AccountDetails::default())
+    }
+
+    /// Open a customer account described by `details`. Its external id, if any, must not be in use.
+    pub fn open_account_with_details(&mut self, currency: Currency, details: AccountDetails) -> Result<AccountId, DomainError> {
+        self.open_account_of_type(currency, AccountType::Liability, details)
     }
 
-    pub fn open_account(&mut self) -> AccountId {
+    /// Open an account of any type in the chart of accounts. Its code, if any, must not be
+    /// in use, and its parent code must belong to an account of the same type.
+    pub fn open_account_of_type(&mut self, currency: Currency, account_type: AccountType, details: AccountDetails) -> Result<AccountId, DomainError> {
         let account_id = AccountId::new_v4();
 
-        info!("Creating new account {}", account_id);
+        if let Some(external_id) = &details.external_id {
+            self.ensure_external_id_free(external_id, account_id)?;
+        }
+
+        if let Some(code) = &details.code {
+            self.ensure_code_free(code)?;
+        }
+
+        if let Some(parent_code) = &details.parent_code {
+            self.ensure_parent(parent_code, account_type)?;
+        }
+
+        info!("Creating new {} {} account {}", currency.code(), account_type, account_id);
 
-        let event = LedgerEvent::account_opened(account_id);
+        let state = BalanceSnapshot { account_id, balances: Balances::default(), version: 0 };
 
-        self.events.push(event);
+        self.record(state, LedgerEvent::account_opened_with_details(account_id, account_type, currency, details))?;
 
-        account_id
+        Ok(account_id)
     }
 
-    pub fn account_exists(&self, account_id: AccountId) -> bool {
-        self.events
-            .iter()
-            .any(|e| e.account_id == account_id)
+    /// Change some of an account's details. Closed accounts can still be updated.
+    pub fn update_details(&mut self, account_id: AccountId, changes: AccountDetailsChanges) -> Result<EventId, DomainError> {
+        let state = self.account_state(account_id)?;
+
+        if let Some(external_id) = changes.new_external_id() {
+            self.ensure_external_id_free(external_id, account_id)?;
+        }
+
+        info!("Updating details of {}", account_id);
+
+        self.record(state, LedgerEvent::account_details_updated(account_id, changes))
     }
 
-    pub fn deposit(&mut self, account_id: AccountId, amount: Money) -> Result<EventId, DomainError> {
-        if !self.account_exists(account_id) {
-            return Err(DomainError::AccountNotFound)
+    /// The account's details as of its latest event.
+    pub fn account_details(&self, account_id: AccountId) -> Result<AccountDetails, DomainError> {
+        Ok(Self::fold_details(self.events_for_account(account_id)?.iter()))
+    }
+
+    /// The account currently holding `external_id`.
+    pub fn find_by_external_id(&mut self, external_id: &str) -> Result<AccountId, DomainError> {
+        self.catch_up_indexes()?;
+
+        self.external_ids.get(external_id).copied().ok_or(DomainError::AccountNotFound)
+    }
+
+    pub fn account_exists(&self, account_id: AccountId) -> Result<bool, DomainError> {
+        match self.account_state(account_id) {
+            Ok(_) => Ok(true),
+            Err(DomainError::AccountNotFound) => Ok(false),
+            Err(err) => Err(err),
         }
+    }
+
+    pub fn deposit(&mut self, account_id: AccountId, amount: Money) -> Result<EventId, DomainError> {
+        self.deposit_with_value_date(account_id, amount, None)
+    }
+
+    /// Deposit money that counts from `value_date` rather than from today. It is still
+    /// booked now, and available straight away whichever day it counts from.
+    pub fn deposit_with_value_date(&mut self, account_id: AccountId, amount: Money, value_date: Option<Date>) -> Result<EventId, DomainError> {
+        let state = self.account_state(account_id)?;
 
         info!("Depositing {} to {}", amount, account_id);
 
-        let event = LedgerEvent::deposit(account_id, amount);
-        let id = event.id;
+        Self::ensure_credits_allowed(&state)?;
+
+        if let Some(value_date) = value_date {
+            self.ensure_value_date_allowed(value_date)?;
+        }
 
-        self.events.push(event);
+        let cash = self.system_account_state(SystemAccount::CashInBank)?;
 
-        Ok(id)
+        self.record_all(vec![state, cash], LedgerEvent::deposit_via(account_id, SystemAccount::CashInBank.id(), amount, value_date))
     }
 
     pub fn withdraw(&mut self, account_id: AccountId, amount: Money) -> Result<EventId, DomainError> {
-        if !self.account_exists(account_id) {
-            return Err(DomainError::AccountNotFound)
-        }
+        self.withdraw_with_value_date(account_id, amount, None)
+    }
+
+    /// Withdraw money that counts as gone from `value_date` rather than from today. The
+    /// funds check is against the balance now, whatever day it counts from.
+    pub fn withdraw_with_value_date(&mut self, account_id: AccountId, amount: Money, value_date: Option<Date>) -> Result<EventId, DomainError> {
+        let state = self.account_state(account_id)?;
 
         info!("Withdrawing {} from {}", amount, account_id);
 
-        let balance = self.balance_for_account(account_id)?;
+        Self::ensure_debits_allowed(&state)?;
+        Self::ensure_funds(&state, amount)?;
 
-        if balance.amount() < amount.amount() {
-            return Err(DomainError::InsufficientFunds { required_minor: amount.amount(), available_minor: balance.amount() });
+        if let Some(value_date) = value_date {
+            self.ensure_value_date_allowed(value_date)?;
         }
 
-        let event = LedgerEvent::withdraw(account_id, amount);
-        let id = event.id;
+        let cash = self.system_account_state(SystemAccount::CashInBank)?;
+
+        // The balance check only holds if nobody appended since `state` was read.
+        self.record_all(vec![state, cash], LedgerEvent::withdraw_via(account_id, SystemAccount::CashInBank.id(), amount, value_date))
+    }
 
-        self.events.push(event);
+    /// Reserve `amount` for a later capture. The ledger balance is unchanged, but the held
+    /// amount is no longer available to withdraw, transfer, convert or hold again.
+    pub fn authorize(&mut self, account_id: AccountId, amount: Money) -> Result<HoldId, DomainError> {
+        let state = self.account_state(account_id)?;
 
-        Ok(id)
+        info!("Authorizing a hold of {} on {}", amount, account_id);
+
+        Self::ensure_debits_allowed(&state)?;
+        Self::ensure_funds(&state, amount)?;
+
+        let hold_id = HoldId::new_v4();
+
+        self.record(state, LedgerEvent::hold_authorized(account_id, hold_id, amount))?;
+
+        Ok(hold_id)
     }
 
-    pub fn balance_for_account(&self, account_id: AccountId) -> Result<Money, DomainError> {
-        let events = self.events_for_account(account_id)?;
+    /// Take some or, if `amount` is `None`, all of a hold off the balance. Whatever is not
+    /// captured is released, so a hold can only be captured once.
+    pub fn capture(&mut self, account_id: AccountId, hold_id: HoldId, amount: Option<Money>) -> Result<EventId, DomainError> {
+        let state = self.account_state(account_id)?;
+        Self::ensure_debits_allowed(&state)?;
 
-        // Only supporting GBP for now
-        let mut balance = Money::zero(Currency::Gbp);
+        let held = state.balances.hold(hold_id).ok_or(DomainError::HoldNotFound)?;
+        let amount = amount.unwrap_or(held);
 
-        for event in events {
-            match event.payload {
-                LedgerEventPayload::Deposit { amount } => {
-                    balance = balance.checked_add(amount).map_err(DomainError::InvalidMoney)?
+        if amount.currency() != held.currency() {
+            return Err(MoneyError::CurrencyMismatch(held.currency(), amount.currency()).into())
+        }
+
+        if amount.amount() > held.amount() {
+            return Err(DomainError::CaptureExceedsHold { held_minor: held.amount(), requested_minor: amount.amount() })
+        }
+
+        info!("Capturing {} of hold {} on {}", amount, hold_id, account_id);
+
+        let cash = self.system_account_state(SystemAccount::CashInBank)?;
+
+        // The funds were reserved when the hold was authorized, so there's nothing to check.
+        self.record_all(vec![state, cash], LedgerEvent::hold_captured(account_id, SystemAccount::CashInBank.id(), hold_id, amount))
+    }
+
+    /// Release a hold without taking anything.
+    pub fn void(&mut self, account_id: AccountId, hold_id: HoldId) -> Result<EventId, DomainError> {
+        let state = self.account_state(account_id)?;
+        state.balances.hold(hold_id).ok_or(DomainError::HoldNotFound)?;
+
+        info!("Voiding hold {} on {}", hold_id, account_id);
+
+        self.record(state, LedgerEvent::hold_voided(account_id, hold_id))
+    }
+
+    /// Release every open hold authorized at least the hold TTL before `now`. Returns how
+    /// many were expired. A hold that can't be expired now is logged and left for the next call.
+    /// One whose expiry would fall past the end of time never expires.
+    pub fn expire_holds(&mut self, now: OffsetDateTime) -> Result<usize, DomainError> {
+        self.catch_up_indexes()?;
+
+        let ttl = time::Duration::try_from(self.hold_ttl).ok();
+
+        let due: Vec<_> = self.open_holds
+            .iter()
+            .filter(|(_, (_, authorized_at))| {
+                ttl.and_then(|ttl| authorized_at.checked_add(ttl)).is_some_and(|expires_at| expires_at <= now)
+            })
+            .map(|(hold_id, (account_id, _))| (*hold_id, *account_id))
+            .collect();
+
+        let mut expired = 0;
+
+        for (hold_id, account_id) in due {
+            let result = self.account_state(account_id).and_then(|state| {
+                state.balances.hold(hold_id).ok_or(DomainError::HoldNotFound)?;
+                self.record(state, LedgerEvent::hold_expired(account_id, hold_id))
+            });
+
+            match result {
+                Ok(_) => {
+                    info!("Expired hold {} on {}", hold_id, account_id);
+                    self.open_holds.remove(&hold_id);
+                    expired += 1;
                 }
-                LedgerEventPayload::Withdraw { amount } => {
-                    balance = balance.checked_sub(amount).map_err(DomainError::InvalidMoney)?
+                Err(err) => warn!("Failed to expire hold {} on {}: {}", hold_id, account_id, err),
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Set up a standing order taking `payment` from `account_id` every `frequency`, first at
+    /// `starts_at` or straight away. [`Ledger::run_due_payments`] makes the runs.
+    pub fn schedule_payment(&mut self, account_id: AccountId, payment: ScheduledPayment, frequency: Frequency, starts_at: Option<OffsetDateTime>) -> Result<ScheduleId, DomainError> {
+        let state = self.account_state(account_id)?;
+
+        Self::ensure_debits_allowed(&state)?;
+
+        if let ScheduledPayment::Transfer { to, .. } = payment {
+            if to == account_id {
+                return Err(DomainError::SelfTransfer)
+            }
+
+            Self::ensure_credits_allowed(&self.account_state(to)?)?;
+        }
+
+        let now = (self.clock)();
+        let starts_at = starts_at.unwrap_or(now);
+
+        if starts_at < now {
+            return Err(DomainError::InvalidSchedule("the first run can't be in the past".to_string()))
+        }
+
+        let schedule_id = ScheduleId::new_v4();
+
+        info!("Scheduling {} from {} {:?} as {}", payment.amount(), account_id, frequency, schedule_id);
+
+        self.record(state, LedgerEvent::payment_scheduled(account_id, schedule_id, payment, frequency, starts_at))?;
+
+        Ok(schedule_id)
+    }
+
+    /// A standing order as of the latest event.
+    pub fn schedule(&mut self, schedule_id: ScheduleId) -> Result<Schedule, DomainError> {
+        self.catch_up_indexes()?;
+
+        self.schedules.get(&schedule_id).cloned().ok_or(DomainError::ScheduleNotFound)
+    }
+
+    /// Stop making runs until resumed. Runs falling due meanwhile are skipped.
+    pub fn pause_schedule(&mut self, schedule_id: ScheduleId) -> Result<EventId, DomainError> {
+        let schedule = self.schedule(schedule_id)?;
+
+        match schedule.status {
+            ScheduleStatus::Active => {}
+            ScheduleStatus::Paused { .. } => return Err(DomainError::SchedulePaused),
+            ScheduleStatus::Cancelled => return Err(DomainError::ScheduleCancelled),
+        }
+
+        let state = self.account_state(schedule.account_id)?;
+
+        info!("Pausing schedule {}", schedule_id);
+
+        self.record(state, LedgerEvent::schedule_paused(schedule.account_id, schedule_id))
+    }
+
+    pub fn resume_schedule(&mut self, schedule_id: ScheduleId) -> Result<EventId, DomainError> {
+        let schedule = self.schedule(schedule_id)?;
+
+        match schedule.status {
+            ScheduleStatus::Paused { .. } => {}
+            ScheduleStatus::Active => return Err(DomainError::ScheduleNotPaused),
+            ScheduleStatus::Cancelled => return Err(DomainError::ScheduleCancelled),
+        }
+
+        let state = self.account_state(schedule.account_id)?;
+
+        info!("Resuming schedule {}", schedule_id);
+
+        self.record(state, LedgerEvent::schedule_resumed(schedule.account_id, schedule_id))
+    }
+
+    /// Stop making runs for good. Paused schedules can be cancelled too.
+    pub fn cancel_schedule(&mut self, schedule_id: ScheduleId) -> Result<EventId, DomainError> {
+        let schedule = self.schedule(schedule_id)?;
+
+        if schedule.status == ScheduleStatus::Cancelled {
+            return Err(DomainError::ScheduleCancelled)
+        }
+
+        let state = self.account_state(schedule.account_id)?;
+
+        info!("Cancelling schedule {}", schedule_id);
+
+        self.record(state, LedgerEvent::schedule_cancelled(schedule.account_id, schedule_id))
+    }
+
+    /// Make every run of every active standing order that has fallen due by `now`, oldest
+    /// first, and return how many were made or failed. Each run is recorded in the same
+    /// append as its payment, so after downtime every missed run is made exactly once. A
+    /// run that can't be made, say for insufficient funds, is recorded as failed and not
+    /// retried. One that hits a concurrent write or a storage error is left for next time.
+    pub fn run_due_payments(&mut self, now: OffsetDateTime) -> Result<usize, DomainError> {
+        self.catch_up_indexes()?;
+
+        let mut due: Vec<_> = self.schedules
+            .values()
+            .filter_map(|schedule| schedule.next_run_at().filter(|due_at| *due_at <= now).map(|due_at| (due_at, schedule.schedule_id)))
+            .collect();
+        due.sort();
+
+        let mut runs = 0;
+
+        for (_, schedule_id) in due {
+            loop {
+                let schedule = self.schedule(schedule_id)?;
+
+                let Some(due_at) = schedule.next_run_at().filter(|due_at| *due_at <= now) else {
+                    break
+                };
+
+                match self.run_scheduled_payment(&schedule, due_at) {
+                    Ok(()) => runs += 1,
+                    Err(err) => {
+                        warn!("Failed to run schedule {} due {}: {}", schedule_id, due_at, err);
+                        break
+                    }
                 }
-                _ => {}
             }
         }
 
-        Ok(balance)
+        Ok(runs)
     }
 
-}
+    /// Make one run, or record why it couldn't be made. Errs only if neither was recorded.
+    fn run_scheduled_payment(&mut self, schedule: &Schedule, due_at: OffsetDateTime) -> Result<(), DomainError> {
+        let state = self.account_state(schedule.account_id)?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::{Currency, Money, events::LedgerEventPayload};
+        // A run made after the day it fell due, say after downtime, counts from that day. It
+        // isn't held to the backdate window, which only limits what callers can ask for.
+        let due_on = due_at.to_offset(UtcOffset::UTC).date();
+        let value_date = (due_on < self.today()).then_some(due_on);
 
-    #[test]
-    fn deposit_into_existing_account_appends_event() {
-        let mut ledger = Ledger::new();
-        let account_id = ledger.open_account();
+        let payment = match schedule.payment {
+            ScheduledPayment::Withdrawal { amount } => self.scheduled_withdrawal(&state, amount, value_date),
+            ScheduledPayment::Transfer { to, amount } => self.scheduled_transfer(&state, to, amount, value_date),
+        };
 
-        let amount = Money::new_minor(10_00, Currency::Gbp).unwrap(); // £10
-        ledger.deposit(account_id, amount).unwrap();
+        match payment {
+            Ok(mut changes) => {
+                info!("Making run of schedule {} due {}", schedule.schedule_id, due_at);
 
-        let events = ledger.events_for_account(account_id).unwrap();
-        assert_eq!(events.len(), 2);
+                let made = LedgerEvent::scheduled_payment_made(schedule.account_id, schedule.schedule_id, due_at, changes[0].1.id);
+                changes.push((state, made));
 
-        // Last event should be a Deposit
-        match &events.last().unwrap().payload {
-            LedgerEventPayload::Deposit { amount, .. } => {
-                assert_eq!(amount.amount(), 10_00);
+                self.commit(changes)
+            }
+            Err(err @ (DomainError::ConcurrencyConflict { .. } | DomainError::Storage(_))) => Err(err),
+            Err(reason) => {
+                warn!("Run of schedule {} due {} failed: {}", schedule.schedule_id, due_at, reason);
+
+                let failed = LedgerEvent::scheduled_payment_failed(schedule.account_id, schedule.schedule_id, due_at, &reason.to_string());
+                let mut changes = vec![(state.clone(), failed)];
+
+                // No later run could get past this either, so stop making them.
+                if matches!(
+                    reason,
+                    DomainError::AccountClosed | DomainError::AccountNotFound | DomainError::SystemAccount | DomainError::JournalOnly(_) | DomainError::SelfTransfer
+                ) {
+                    warn!("Cancelling schedule {}", schedule.schedule_id);
+                    changes.push((state, LedgerEvent::schedule_cancelled(schedule.account_id, schedule.schedule_id)));
+                }
+
+                self.commit(changes)
             }
-            other => panic!("expected Deposit event, got {other:?}"),
         }
     }
 
-    #[test]
-    fn deposit_into_unknown_account_fails() {
-        let mut ledger = Ledger::new();
-        let fake_account = AccountId::new_v4();
+    /// The events of a withdrawal from `state`'s account, if it can be made.
+    fn scheduled_withdrawal(&mut self, state: &BalanceSnapshot, amount: Money, value_date: Option<Date>) -> Result<Vec<(BalanceSnapshot, LedgerEvent)>, DomainError> {
+        Self::ensure_debits_allowed(state)?;
+        Self::ensure_funds(state, amount)?;
 
-        let amount = Money::new_minor(5_00, Currency::Gbp).unwrap();
-        let err = ledger.deposit(fake_account, amount).unwrap_err();
+        let cash = self.system_account_state(SystemAccount::CashInBank)?;
+        let events = LedgerEvent::withdraw_via(state.account_id, SystemAccount::CashInBank.id(), amount, value_date);
 
-        matches!(err, DomainError::AccountNotFound);
+        Ok(vec![state.clone(), cash].into_iter().zip(events).collect())
     }
 
-    #[test]
-    fn withdrawal_reduces_balance_when_sufficient_funds() {
-        let mut ledger = Ledger::new();
-        let account = ledger.open_account();
+    /// The events of a transfer from `state`'s account to `to`, if it can be made.
+    fn scheduled_transfer(&mut self, state: &BalanceSnapshot, to: AccountId, amount: Money, value_date: Option<Date>) -> Result<Vec<(BalanceSnapshot, LedgerEvent)>, DomainError> {
+        let to_state = self.account_state(to)?;
 
-        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
-        let four = Money::new_minor(4_00, Currency::Gbp).unwrap();
+        Self::ensure_debits_allowed(state)?;
+        Self::ensure_credits_allowed(&to_state)?;
+        Self::ensure_funds(state, amount)?;
 
-        ledger.deposit(account, ten).unwrap();
-        ledger.withdraw(account, four).unwrap();
+        let (debit, credit) = LedgerEvent::transfer(TransferId::new_v4(), state.account_id, to, amount, value_date);
 
-        let balance = ledger.balance_for_account(account).unwrap();
-        assert_eq!(balance.amount(), 6_00);
+        Ok(vec![(state.clone(), debit), (to_state, credit)])
     }
 
-    #[test]
-    fn withdrawal_fails_when_insufficient_funds() {
-        let mut ledger = Ledger::new();
-        let account = ledger.open_account();
+    /// Stop all debits, and credits too unless `allow_credits` is set. Freezing a frozen
+    /// account replaces whether it accepts credits.
+    pub fn freeze(&mut self, account_id: AccountId, allow_credits: bool) -> Result<EventId, DomainError> {
+        Self::ensure_customer_account(account_id)?;
+        let state = self.account_state(account_id)?;
 
-        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
-        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+        if state.balances.status() == AccountStatus::Closed {
+            return Err(DomainError::AccountClosed)
+        }
 
-        ledger.deposit(account, five).unwrap();
+        info!("Freezing {}", account_id);
 
-        let err = ledger.withdraw(account, ten).unwrap_err();
-        match err {
-            DomainError::InsufficientFunds {
-                required_minor,
-                available_minor,
-            } => {
-                assert_eq!(required_minor, 10_00);
-                assert_eq!(available_minor, 5_00);
-            }
-            other => panic!("expected InsufficientFunds, got {other:?}"),
+        self.record(state, LedgerEvent::account_frozen(account_id, allow_credits))
+    }
+
+    pub fn unfreeze(&mut self, account_id: AccountId) -> Result<EventId, DomainError> {
+        Self::ensure_customer_account(account_id)?;
+        let state = self.account_state(account_id)?;
+
+        match state.balances.status() {
+            AccountStatus::Frozen { .. } => {}
+            AccountStatus::Open => return Err(DomainError::AccountNotFrozen),
+            AccountStatus::Closed => return Err(DomainError::AccountClosed),
+        }
+
+        info!("Unfreezing {}", account_id);
+
+        self.record(state, LedgerEvent::account_unfrozen(account_id))
+    }
+
+    /// Close the account for good. Every sub-balance must be zero and no holds open.
+    /// Frozen accounts can be closed too. Standing orders paid from the account are
+    /// cancelled in the same append.
+    pub fn close(&mut self, account_id: AccountId) -> Result<EventId, DomainError> {
+        Self::ensure_customer_account(account_id)?;
+        let state = self.account_state(account_id)?;
+
+        if state.balances.status() == AccountStatus::Closed {
+            return Err(DomainError::AccountClosed)
+        }
+
+        if !state.balances.is_settled() {
+            return Err(DomainError::AccountNotSettled)
+        }
+
+        info!("Closing {}", account_id);
+
+        self.catch_up_indexes()?;
+
+        let mut changes: Vec<_> = self.schedules
+            .values()
+            .filter(|schedule| schedule.account_id == account_id && schedule.status != ScheduleStatus::Cancelled)
+            .map(|schedule| (state.clone(), LedgerEvent::schedule_cancelled(account_id, schedule.schedule_id)))
+            .collect();
+
+        let closed = LedgerEvent::account_closed(account_id);
+        let id = closed.id;
+        changes.push((state, closed));
+
+        self.commit(changes)?;
+
+        Ok(id)
+    }
+
+    /// Arrange an overdraft in the limit's currency, replacing any earlier limit in it.
+    /// A zero limit removes the overdraft. Lowering the limit below what is already
+    /// overdrawn is allowed, but then nothing more can be taken out until the account
+    /// is back within it.
+    pub fn set_overdraft_limit(&mut self, account_id: AccountId, limit: Money) -> Result<EventId, DomainError> {
+        Self::ensure_customer_account(account_id)?;
+        let state = self.account_state(account_id)?;
+        Self::ensure_wallet(&state)?;
+
+        info!("Setting overdraft limit of {} on {}", limit, account_id);
+
+        if state.balances.status() == AccountStatus::Closed {
+            return Err(DomainError::AccountClosed)
+        }
+
+        self.record(state, LedgerEvent::overdraft_limit_set(account_id, limit))
+    }
+
+    /// Move money between two accounts. Both legs are appended atomically, or neither is.
+    pub fn transfer(&mut self, from: AccountId, to: AccountId, amount: Money) -> Result<TransferId, DomainError> {
+        if from == to {
+            return Err(DomainError::SelfTransfer)
+        }
+
+        let from_state = self.account_state(from)?;
+        let to_state = self.account_state(to)?;
+
+        info!("Transferring {} from {} to {}", amount, from, to);
+
+        Self::ensure_debits_allowed(&from_state)?;
+        Self::ensure_credits_allowed(&to_state)?;
+        Self::ensure_funds(&from_state, amount)?;
+
+        let transfer_id = TransferId::new_v4();
+        let (debit, credit) = LedgerEvent::transfer(transfer_id, from, to, amount, None);
+
+        self.commit(vec![(from_state, debit), (to_state, credit)])?;
+
+        Ok(transfer_id)
+    }
+
+    /// Exchange `source` for `target_currency` at the table rate, taking it from `from` and
+    /// paying the result into `to`, which may be the same account. All events are appended
+    /// atomically. The target is rounded down, see [`crate::domain::fx`].
+    pub fn convert(&mut self, from: AccountId, to: AccountId, source: Money, target_currency: Currency) -> Result<Conversion, DomainError> {
+        let rate = self.rates.rate(source.currency(), target_currency)?;
+        let target = rate.convert(source, target_currency)?;
+
+        let from_state = self.account_state(from)?;
+
+        info!("Converting {} from {} to {} in {}", source, from, target, to);
+
+        Self::ensure_debits_allowed(&from_state)?;
+        Self::ensure_funds(&from_state, source)?;
+
+        let conversion = Conversion {
+            conversion_id: ConversionId::new_v4(),
+            source,
+            target,
+            rate,
+            rate_source: self.rates.source().to_string(),
+        };
+
+        let events = LedgerEvent::currency_converted(from, to, SystemAccount::FxPosition.id(), &conversion);
+
+        let mut states = vec![from_state];
+
+        if from != to {
+            let to_state = self.account_state(to)?;
+            Self::ensure_credits_allowed(&to_state)?;
+            states.push(to_state);
+        }
+
+        states.push(self.system_account_state(SystemAccount::FxPosition)?);
+
+        self.commit(states.into_iter().zip(events).collect())?;
+
+        Ok(conversion)
+    }
+
+    pub fn account_status(&self, account_id: AccountId) -> Result<AccountStatus, DomainError> {
+        Ok(self.account_state(account_id)?.balances.status())
+    }
+
+    pub fn account_type(&self, account_id: AccountId) -> Result<AccountType, DomainError> {
+        Ok(self.account_state(account_id)?.balances.account_type())
+    }
+
+    /// Post a balanced journal entry, appending it to every account it touches or to none.
+    /// Customer wallets it debits need the funds, as for a withdrawal. Accounts of other
+    /// types can go either way.
+    pub fn post_entry(&mut self, entry: JournalEntry) -> Result<EntryId, DomainError> {
+        info!("Posting journal entry {}", entry.entry_id);
+
+        let mut states = Vec::new();
+
+        for account_id in entry.accounts() {
+            let state = match SystemAccount::from_id(account_id) {
+                Some(system) => self.system_account_state(system)?,
+                None => {
+                    let state = self.account_state(account_id)?;
+                    Self::ensure_legs_allowed(&state, &entry.legs)?;
+                    state
+                }
+            };
+
+            states.push(state);
         }
+
+        let events = LedgerEvent::journal_entry_posted(&entry);
+
+        self.commit(states.into_iter().zip(events).collect())?;
+
+        Ok(entry.entry_id)
+    }
+
+    /// Undo the money movement `event_id` records with an event on every account it touched,
+    /// each naming the original event on that account. A movement can only be reversed
+    /// once, and the accounts it now debits must allow it and, for customer wallets, have
+    /// the funds. Returns the id of the reversal on the original event's account.
+    pub fn reverse(&mut self, event_id: EventId, reason: &str) -> Result<EventId, DomainError> {
+        let original = self.find_event(event_id)?;
+
+        let legs = match (&original.payload, original.legs()) {
+            (LedgerEventPayload::EventReversed { .. }, _) => {
+                return Err(DomainError::NotReversible(format!("event {event_id} is itself a reversal")))
+            }
+            (_, None) if SystemAccount::from_id(original.account_id).is_some() => {
+                return Err(DomainError::NotReversible(format!("event {event_id} is the other side of a movement, reverse it on the customer's account")))
+            }
+            (_, None) => return Err(DomainError::NotReversible(format!("event {event_id} moves no money"))),
+            (_, Some(legs)) => legs,
+        };
+
+        let legs: Vec<_> = legs
+            .into_iter()
+            .map(|leg| JournalLeg { side: leg.side.opposite(), ..leg })
+            .collect();
+
+        // Movements from before double entry have only the one side, so undoing them on
+        // their own would put the books out of balance the other way.
+        let legs = match JournalEntry::new(legs, None) {
+            Ok(entry) => entry.legs,
+            Err(DomainError::InvalidEntry(_) | DomainError::UnbalancedEntry { .. }) => {
+                return Err(DomainError::NotReversible(format!("event {event_id} was recorded without a balancing other side")))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let stream = self.events_for_account(original.account_id)?;
+        let reversal = stream.iter().find(|e| matches!(e.payload, LedgerEventPayload::EventReversed { reverses, .. } if reverses == event_id));
+
+        if let Some(reversal) = reversal {
+            return Err(DomainError::AlreadyReversed(reversal.id))
+        }
+
+        info!("Reversing {} on {}: {}", event_id, original.account_id, reason);
+
+        let mut accounts = vec![original.account_id];
+        for leg in &legs {
+            if !accounts.contains(&leg.account_id) {
+                accounts.push(leg.account_id);
+            }
+        }
+
+        let mut states = Vec::new();
+        let mut originals = Vec::new();
+
+        for account_id in accounts {
+            let state = match SystemAccount::from_id(account_id) {
+                Some(system) => self.system_account_state(system)?,
+                None => {
+                    let state = self.account_state(account_id)?;
+                    Self::ensure_legs_allowed(&state, &legs)?;
+                    state
+                }
+            };
+
+            let copy = if account_id == original.account_id {
+                original.id
+            } else {
+                self.events_for_account(account_id)?
+                    .iter()
+                    .rev()
+                    .find(|event| event.same_movement(&original))
+                    .map(|event| event.id)
+                    .ok_or(DomainError::EventNotFound)?
+            };
+
+            states.push(state);
+            originals.push((account_id, copy));
+        }
+
+        self.record_all(states, LedgerEvent::event_reversed(&originals, reason, &legs))
+    }
+
+    /// Every account's balance in every currency it holds, from a full replay of the log.
+    ///
+    /// Debits only equal credits for events recorded with double entry. Deposits and
+    /// withdrawals from before it have no other side and are never rewritten, since that
+    /// would break the hash chain, so a log holding them leaves the trial balance out by
+    /// exactly their total.
+    pub fn trial_balance(&self) -> Result<TrialBalance, DomainError> {
+        let events = self.store.read_all_from(0)?;
+
+        let mut trial_balance = TrialBalance::default();
+
+        for (account_id, events) in Self::streams(&events) {
+            let balances = Self::fold_balances(Balances::default(), events)?;
+            let normal_side = balances.account_type().normal_side();
+
+            for balance in balances.iter() {
+                let magnitude = balance.amount().checked_abs().ok_or(MoneyError::Overflow)?;
+                let on_debit_side = (normal_side == Side::Debit) != balance.is_negative();
+                let (debit, credit) = if on_debit_side { (magnitude, 0) } else { (0, magnitude) };
+
+                if magnitude > 0 {
+                    trial_balance.push(TrialBalanceLine {
+                        account_id,
+                        currency: balance.currency(),
+                        debit: Money::new_minor(debit, balance.currency())?,
+                        credit: Money::new_minor(credit, balance.currency())?,
+                    })?;
+                }
+            }
+        }
+
+        Ok(trial_balance)
+    }
+
+    /// How much each income and expense account moved after `from` up to and including `to`.
+    pub fn income_statement(&self, from: OffsetDateTime, to: OffsetDateTime) -> Result<IncomeStatement, DomainError> {
+        let events = self.store.read_all_from(0)?;
+
+        Self::income_statement_between(&Self::chart_at(&events, from)?, &Self::chart_at(&events, to)?, from, to)
+    }
+
+    /// Assets, liabilities and equity as of `to`, with the earnings from before `from` and
+    /// from the period up to `to` that haven't been moved into equity.
+    pub fn balance_sheet(&self, from: OffsetDateTime, to: OffsetDateTime) -> Result<BalanceSheet, DomainError> {
+        let events = self.store.read_all_from(0)?;
+
+        let opening = Self::chart_at(&events, from)?;
+        let closing = Self::chart_at(&events, to)?;
+
+        let before = Self::income_statement_between(&[], &opening, OffsetDateTime::UNIX_EPOCH, from)?;
+        let during = Self::income_statement_between(&opening, &closing, from, to)?;
+
+        Ok(BalanceSheet {
+            from,
+            to,
+            assets: Self::statement_lines(&[], &closing, AccountType::Asset)?,
+            liabilities: Self::statement_lines(&[], &closing, AccountType::Liability)?,
+            equity: Self::statement_lines(&[], &closing, AccountType::Equity)?,
+            retained_earnings: before.net_income()?,
+            net_income: during.net_income()?,
+        })
+    }
+
+    /// Every sub-balance the account holds, one per currency.
+    pub fn balance_for_account(&self, account_id: AccountId) -> Result<Balances, DomainError> {
+        Ok(self.account_state(account_id)?.balances)
+    }
+
+    /// Balance as it stood at an earlier point, folding only the events up to it.
+    pub fn balance_at(&self, account_id: AccountId, as_of: AsOf) -> Result<Balances, DomainError> {
+        let events = self.events_for_account(account_id)?;
+
+        match as_of {
+            AsOf::Time(at) => Self::fold_balances(Balances::default(), events.iter().filter(|e| e.created_at <= at)),
+            AsOf::Version(version) => Self::fold_balances(Balances::default(), events.iter().take(usize::try_from(version).unwrap_or(usize::MAX))),
+            AsOf::ValueDate { date, known_at } => {
+                Self::fold_balances(Balances::default(), events.iter().filter(|e| e.value_date() <= date && e.created_at <= known_at))
+            }
+        }
+    }
+
+    /// Simple interest on the account's `currency` balance for each day from `from` up to,
+    /// but not including, `to`, at `annual_rate_bps` hundredths of a percent a year over
+    /// 365 days. Each day earns on its closing balance by value date, as known now, so a
+    /// backdated deposit earns from the day it counts from. Overdrawn days earn nothing.
+    /// The total is rounded down to the minor unit.
+    pub fn accrued_interest(&self, account_id: AccountId, currency: Currency, annual_rate_bps: u32, from: Date, to: Date) -> Result<Money, DomainError> {
+        let events = self.events_for_account(account_id)?;
+
+        // Opening the account resets its balances, so it counts from before any other event
+        // whatever day the first deposit counts from.
+        let counts_from = |event: &LedgerEvent| match event.payload {
+            LedgerEventPayload::AccountOpened { .. } => Date::MIN,
+            _ => event.value_date(),
+        };
+
+        let mut by_value_date: Vec<_> = events.iter().collect();
+        by_value_date.sort_by_key(|e| counts_from(e));
+
+        let mut pending = by_value_date.into_iter().peekable();
+        let mut balances = Balances::default();
+        let mut balance_days: i128 = 0;
+        let mut day = from;
+
+        while day < to {
+            while let Some(event) = pending.next_if(|e| counts_from(e) <= day) {
+                balances = Self::fold_balances(balances, [event])?;
+            }
+
+            balance_days += i128::from(balances.get(currency).amount().max(0));
+            day = day.next_day().ok_or(MoneyError::Overflow)?;
+        }
+
+        let interest = balance_days * i128::from(annual_rate_bps) / (10_000 * 365);
+
+        Ok(Money::new_minor(i64::try_from(interest).map_err(|_| MoneyError::Overflow)?, currency)?)
+    }
+
+    /// Check that the stored snapshot and the cached running balance, each plus the
+    /// events after it, agree with a full replay.
+    pub fn verify_snapshot(&self, account_id: AccountId) -> Result<(), DomainError> {
+        let events = self.events_for_account(account_id)?;
+        let replayed = Self::fold_balances(Balances::default(), events.iter())?;
+
+        let stored = self.store.load_snapshot(account_id)?;
+        let cached = self.states.get(&account_id).cloned();
+
+        for snapshot in stored.into_iter().chain(cached) {
+            let tail = usize::try_from(snapshot.version).ok().and_then(|v| events.get(v..)).unwrap_or(&[]);
+            let from_snapshot = Self::fold_balances(snapshot.balances, tail)?;
+
+            if from_snapshot != replayed {
+                return Err(DomainError::SnapshotMismatch { account_id, from_snapshot: Box::new(from_snapshot), replayed: Box::new(replayed) });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Walk the global log and report the first event whose hash or link doesn't check out.
+    /// Also returns how many events were checked.
+    pub fn verify_chain(&self) -> Result<(u64, Option<ChainBreak>), DomainError> {
+        let events = self.store.read_all_from(0)?;
+
+        Ok((events.len() as u64, chain::verify(&events)))
+    }
+
+    /// Merkle root over every event in the log, along with the number of events it covers.
+    pub fn merkle_root(&self) -> Result<(u64, EventHash), DomainError> {
+        let hashes = self.event_hashes()?;
+
+        Ok((hashes.len() as u64, merkle::root(&hashes)))
+    }
+
+    /// Proof that the event is included under the current Merkle root.
+    pub fn inclusion_proof(&self, event_id: EventId) -> Result<InclusionProof, DomainError> {
+        let events = self.store.read_all_from(0)?;
+
+        let index = events
+            .iter()
+            .position(|e| e.id == event_id)
+            .ok_or(DomainError::EventNotFound)?;
+
+        let hashes: Vec<_> = events.iter().map(|e| e.hash).collect();
+
+        merkle::inclusion_proof(&hashes, index).ok_or(DomainError::EventNotFound)
+    }
+
+    fn find_event(&mut self, event_id: EventId) -> Result<LedgerEvent, DomainError> {
+        self.catch_up_indexes()?;
+
+        let account_id = *self.event_accounts.get(&event_id).ok_or(DomainError::EventNotFound)?;
+
+        self.events_for_account(account_id)?
+            .iter()
+            .find(|e| e.id == event_id)
+            .cloned()
+            .ok_or(DomainError::EventNotFound)
+    }
+
+    fn event_hashes(&self) -> Result<Vec<EventHash>, DomainError> {
+        Ok(self.store.read_all_from(0)?.iter().map(|e| e.hash).collect())
+    }
+
+    fn ensure_code_free(&mut self, code: &str) -> Result<(), DomainError> {
+        self.catch_up_indexes()?;
+
+        match self.codes.contains_key(code) {
+            true => Err(DomainError::AccountCodeTaken(code.to_string())),
+            false => Ok(()),
+        }
+    }
+
+    fn ensure_parent(&mut self, parent_code: &str, account_type: AccountType) -> Result<(), DomainError> {
+        self.catch_up_indexes()?;
+
+        match self.codes.get(parent_code) {
+            None => Err(DomainError::InvalidParent(format!("no account has code {parent_code}"))),
+            Some(parent_type) if *parent_type != account_type => {
+                Err(DomainError::InvalidParent(format!("account {parent_code} is of type {parent_type}, not {account_type}")))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Today in UTC, by the ledger's clock.
+    fn today(&self) -> Date {
+        (self.clock)().to_offset(UtcOffset::UTC).date()
+    }
+
+    /// Value dates must fall within the configured number of whole days either side of today.
+    fn ensure_value_date_allowed(&self, value_date: Date) -> Result<(), DomainError> {
+        let whole_days = |window: Duration| {
+            let secs = window.as_secs() - window.as_secs() % (24 * 60 * 60);
+            time::Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX))
+        };
+
+        let today = self.today();
+        let earliest = today.saturating_sub(whole_days(self.max_backdate));
+        let latest = today.saturating_add(whole_days(self.max_forward_date));
+
+        if value_date < earliest || value_date > latest {
+            return Err(DomainError::ValueDateOutOfRange { value_date, earliest, latest })
+        }
+
+        Ok(())
+    }
+
+    fn ensure_external_id_free(&mut self, external_id: &str, account_id: AccountId) -> Result<(), DomainError> {
+        self.catch_up_indexes()?;
+
+        match self.external_ids.get(external_id) {
+            Some(holder) if *holder != account_id => Err(DomainError::ExternalIdTaken(external_id.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bring the open hold, external id, code and schedule indexes up to date with the global log.
+    fn catch_up_indexes(&mut self) -> Result<(), DomainError> {
+        let events = self.store.read_all_from(self.indexed_position)?;
+        self.indexed_position += events.len() as u64;
+
+        for event in &events {
+            self.event_accounts.insert(event.id, event.account_id);
+
+            match &event.payload {
+                LedgerEventPayload::AccountOpened { account_type, details, .. } => {
+                    if let Some(external_id) = &details.external_id {
+                        self.external_ids.insert(external_id.clone(), event.account_id);
+                    }
+
+                    if let Some(code) = &details.code {
+                        self.codes.insert(code.clone(), AccountType::recorded_for(event.account_id, *account_type));
+                    }
+                }
+                LedgerEventPayload::AccountDetailsUpdated { changes } if changes.external_id.is_some() => {
+                    self.external_ids.retain(|_, holder| *holder != event.account_id);
+
+                    if let Some(external_id) = changes.new_external_id() {
+                        self.external_ids.insert(external_id.to_string(), event.account_id);
+                    }
+                }
+                LedgerEventPayload::HoldAuthorized { hold_id, .. } => {
+                    self.open_holds.insert(*hold_id, (event.account_id, event.created_at));
+                }
+                LedgerEventPayload::HoldCaptured { hold_id, .. }
+                | LedgerEventPayload::HoldVoided { hold_id }
+                | LedgerEventPayload::HoldExpired { hold_id } => {
+                    self.open_holds.remove(hold_id);
+                }
+                LedgerEventPayload::PaymentScheduled { schedule_id, payment, frequency, starts_at } => {
+                    self.schedules.insert(*schedule_id, Schedule::new(*schedule_id, event.account_id, *payment, *frequency, *starts_at));
+                }
+                LedgerEventPayload::SchedulePaused { schedule_id } => {
+                    self.schedules.entry(*schedule_id).and_modify(|schedule| schedule.pause(event.created_at));
+                }
+                LedgerEventPayload::ScheduleResumed { schedule_id } => {
+                    self.schedules.entry(*schedule_id).and_modify(|schedule| schedule.resume(event.created_at));
+                }
+                LedgerEventPayload::ScheduleCancelled { schedule_id } => {
+                    self.schedules.entry(*schedule_id).and_modify(Schedule::cancel);
+                }
+                LedgerEventPayload::ScheduledPaymentMade { schedule_id, .. } | LedgerEventPayload::ScheduledPaymentFailed { schedule_id, .. } => {
+                    self.schedules.entry(*schedule_id).and_modify(Schedule::ran);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// System accounts only move as the other side of an operation or through journal entries.
+    fn ensure_customer_account(account_id: AccountId) -> Result<(), DomainError> {
+        match SystemAccount::from_id(account_id) {
+            Some(_) => Err(DomainError::SystemAccount),
+            None => Ok(()),
+        }
+    }
+
+    /// Customer wallets are liabilities. Accounts of other types belong to the business's
+    /// own books and, like system accounts, only move through journal entries.
+    fn ensure_wallet(state: &BalanceSnapshot) -> Result<(), DomainError> {
+        Self::ensure_customer_account(state.account_id)?;
+
+        match state.balances.account_type() {
+            AccountType::Liability => Ok(()),
+            other => Err(DomainError::JournalOnly(other)),
+        }
+    }
+
+    /// Journal entries and reversals may only debit an account that allows debits, and only
+    /// credit one that allows credits. A customer wallet also needs the funds for its net
+    /// debit in each currency.
+    fn ensure_legs_allowed(state: &BalanceSnapshot, legs: &[JournalLeg]) -> Result<(), DomainError> {
+        let legs: Vec<_> = legs.iter().filter(|leg| leg.account_id == state.account_id).collect();
+
+        if legs.iter().any(|leg| leg.side == Side::Debit) {
+            Self::ensure_open_for_debits(state.balances.status())?;
+        }
+
+        if legs.iter().any(|leg| leg.side == Side::Credit) {
+            Self::ensure_open_for_credits(state.balances.status())?;
+        }
+
+        if state.balances.account_type() != AccountType::Liability {
+            return Ok(())
+        }
+
+        let mut net: BTreeMap<Currency, SignedMoney> = BTreeMap::new();
+
+        for leg in legs {
+            let currency = leg.amount.currency();
+            let total = net.get(&currency).copied().unwrap_or(SignedMoney::zero(currency));
+
+            let total = match leg.side {
+                Side::Debit => total.checked_sub(leg.amount)?,
+                Side::Credit => total.checked_add(leg.amount)?,
+            };
+
+            net.insert(currency, total);
+        }
+
+        for total in net.values().filter(|total| total.is_negative()) {
+            let debit = total.amount().checked_neg().ok_or(MoneyError::Overflow)?;
+            Self::ensure_funds(state, Money::new_minor(debit, total.currency())?)?;
+        }
+
+        Ok(())
+    }
+
+    /// A system account's state, opening it first if this is the first time it is needed.
+    fn system_account_state(&mut self, system: SystemAccount) -> Result<BalanceSnapshot, DomainError> {
+        match self.account_state(system.id()) {
+            Err(DomainError::AccountNotFound) => {
+                info!("Opening system account {}", system.name());
+
+                let details = AccountDetails {
+                    display_name: Some(system.name().to_string()),
+                    labels: ["system".to_string()].into(),
+                    ..Default::default()
+                };
+
+                let state = BalanceSnapshot { account_id: system.id(), balances: Balances::default(), version: 0 };
+                self.record(state, LedgerEvent::account_opened_with_details(system.id(), system.account_type(), Currency::Gbp, details))?;
+
+                self.account_state(system.id())
+            }
+            other => other,
+        }
+    }
+
+    fn ensure_debits_allowed(state: &BalanceSnapshot) -> Result<(), DomainError> {
+        Self::ensure_wallet(state)?;
+        Self::ensure_open_for_debits(state.balances.status())
+    }
+
+    fn ensure_credits_allowed(state: &BalanceSnapshot) -> Result<(), DomainError> {
+        Self::ensure_wallet(state)?;
+        Self::ensure_open_for_credits(state.balances.status())
+    }
+
+    fn ensure_open_for_debits(status: AccountStatus) -> Result<(), DomainError> {
+        match status {
+            AccountStatus::Open => Ok(()),
+            AccountStatus::Frozen { .. } => Err(DomainError::AccountFrozen),
+            AccountStatus::Closed => Err(DomainError::AccountClosed),
+        }
+    }
+
+    fn ensure_open_for_credits(status: AccountStatus) -> Result<(), DomainError> {
+        match status {
+            AccountStatus::Closed => Err(DomainError::AccountClosed),
+            status if !status.allows_credits() => Err(DomainError::AccountFrozen),
+            _ => Ok(()),
+        }
+    }
+
+    /// Only the sub-balance in the amount's currency, plus any overdraft on it and less any
+    /// holds on it, can cover it.
+    fn ensure_funds(state: &BalanceSnapshot, amount: Money) -> Result<(), DomainError> {
+        let available = state.balances.available(amount.currency())?;
+
+        if available.amount() < amount.amount() {
+            return Err(DomainError::InsufficientFunds { required_minor: amount.amount(), available_minor: available.amount() });
+        }
+
+        Ok(())
+    }
+
+    /// The log split into each account's stream, in account id order.
+    fn streams(events: &[LedgerEvent]) -> BTreeMap<AccountId, Vec<&LedgerEvent>> {
+        let mut streams: BTreeMap<AccountId, Vec<&LedgerEvent>> = BTreeMap::new();
+
+        for event in events {
+            streams.entry(event.account_id).or_default().push(event);
+        }
+
+        streams
+    }
+
+    /// Every account with events counting from `at` or earlier, with its latest details and
+    /// its balances as of `at`, ordered by code. Accounts without a code come last. Value
+    /// dated events count from the start of their value date, the rest from when they were booked.
+    fn chart_at(events: &[LedgerEvent], at: OffsetDateTime) -> Result<Vec<ChartAccount>, DomainError> {
+        let mut chart = Vec::new();
+
+        for (account_id, events) in Self::streams(events) {
+            let effective: Vec<_> = events.iter().copied().filter(|e| e.effective_at() <= at).collect();
+
+            if effective.is_empty() {
+                continue
+            }
+
+            chart.push(ChartAccount {
+                account_id,
+                details: Self::fold_details(events),
+                balances: Self::fold_balances(Balances::default(), effective)?,
+            });
+        }
+
+        chart.sort_by_key(|account| (account.details.code.is_none(), account.details.code.clone()));
+
+        Ok(chart)
+    }
+
+    /// How much each account of `account_type` moved from `opening` to `closing`, one line
+    /// per currency that moved. Against an empty opening, that is its balance.
+    fn statement_lines(opening: &[ChartAccount], closing: &[ChartAccount], account_type: AccountType) -> Result<Vec<StatementLine>, DomainError> {
+        let opening: HashMap<_, _> = opening.iter().map(|account| (account.account_id, &account.balances)).collect();
+
+        let mut lines = Vec::new();
+
+        for account in closing.iter().filter(|account| account.balances.account_type() == account_type) {
+            for balance in account.balances.iter() {
+                let before = opening
+                    .get(&account.account_id)
+                    .map_or(SignedMoney::zero(balance.currency()), |balances| balances.get(balance.currency()));
+
+                let amount = balance.checked_sub(before)?;
+
+                if amount.amount() != 0 {
+                    lines.push(StatementLine {
+                        account_id: account.account_id,
+                        code: account.details.code.clone(),
+                        parent_code: account.details.parent_code.clone(),
+                        name: account.details.display_name.clone(),
+                        amount,
+                    });
+                }
+            }
+        }
+
+        Ok(lines)
+    }
+
+    fn income_statement_between(opening: &[ChartAccount], closing: &[ChartAccount], from: OffsetDateTime, to: OffsetDateTime) -> Result<IncomeStatement, DomainError> {
+        Ok(IncomeStatement {
+            from,
+            to,
+            income: Self::statement_lines(opening, closing, AccountType::Income)?,
+            expenses: Self::statement_lines(opening, closing, AccountType::Expense)?,
+        })
+    }
+
+    fn fold_details<'a>(events: impl IntoIterator<Item = &'a LedgerEvent>) -> AccountDetails {
+        let mut details = AccountDetails::default();
+
+        for event in events {
+            match &event.payload {
+                LedgerEventPayload::AccountOpened { details: opened_with, .. } => details = opened_with.clone(),
+                LedgerEventPayload::AccountDetailsUpdated { changes } => details.apply(changes),
+                _ => {}
+            }
+        }
+
+        details
+    }
+
+    fn fold_balances<'a>(mut balances: Balances, events: impl IntoIterator<Item = &'a LedgerEvent>) -> Result<Balances, DomainError> {
+        for event in events {
+            // Whether this is the system account's copy of the event, so moves the other way.
+            let is_via = |via: Option<AccountId>| via == Some(event.account_id);
+
+            match event.payload {
+                LedgerEventPayload::AccountOpened { currency, account_type, .. } => {
+                    balances = Balances::opened_in(currency.unwrap_or(Currency::Gbp), AccountType::recorded_for(event.account_id, account_type))
+                }
+                LedgerEventPayload::AccountDetailsUpdated { .. }
+                | LedgerEventPayload::PaymentScheduled { .. }
+                | LedgerEventPayload::SchedulePaused { .. }
+                | LedgerEventPayload::ScheduleResumed { .. }
+                | LedgerEventPayload::ScheduleCancelled { .. }
+                | LedgerEventPayload::ScheduledPaymentMade { .. }
+                | LedgerEventPayload::ScheduledPaymentFailed { .. } => {}
+                LedgerEventPayload::Deposit { amount, via, .. } if is_via(via) => {
+                    balances.debit(amount)?
+                }
+                LedgerEventPayload::Withdrawal { amount, via, .. } if is_via(via) => {
+                    balances.credit(amount)?
+                }
+                LedgerEventPayload::Deposit { amount, .. } | LedgerEventPayload::TransferCredit { amount, .. } => {
+                    balances.credit(amount)?
+                }
+                LedgerEventPayload::Withdrawal { amount, .. } | LedgerEventPayload::TransferDebit { amount, .. } => {
+                    balances.debit(amount)?
+                }
+                LedgerEventPayload::AccountFrozen { allow_credits } => {
+                    balances.set_status(AccountStatus::Frozen { allow_credits })
+                }
+                LedgerEventPayload::AccountUnfrozen => {
+                    balances.set_status(AccountStatus::Open)
+                }
+                LedgerEventPayload::AccountClosed => {
+                    balances.set_status(AccountStatus::Closed)
+                }
+                LedgerEventPayload::HoldAuthorized { hold_id, amount } => {
+                    balances.place_hold(hold_id, amount)
+                }
+                LedgerEventPayload::HoldCaptured { amount, via, .. } if is_via(via) => {
+                    balances.credit(amount)?
+                }
+                LedgerEventPayload::HoldCaptured { hold_id, amount, .. } => {
+                    balances.release_hold(hold_id);
+                    balances.debit(amount)?
+                }
+                LedgerEventPayload::HoldVoided { hold_id } | LedgerEventPayload::HoldExpired { hold_id } => {
+                    balances.release_hold(hold_id);
+                }
+                LedgerEventPayload::OverdraftLimitSet { limit } => {
+                    balances.set_overdraft_limit(limit)
+                }
+                LedgerEventPayload::CurrencyConverted { source, target, via, .. } if is_via(via) => {
+                    balances.credit(source)?;
+                    balances.debit(target)?
+                }
+                LedgerEventPayload::CurrencyConverted { from, to, source, target, .. } => {
+                    if event.account_id == from {
+                        balances.debit(source)?
+                    }
+                    if event.account_id == to {
+                        balances.credit(target)?
+                    }
+                }
+                LedgerEventPayload::JournalEntryPosted { ref legs, .. } | LedgerEventPayload::EventReversed { ref legs, .. } => {
+                    for leg in legs.iter().filter(|leg| leg.account_id == event.account_id) {
+                        match leg.side {
+                            Side::Debit => balances.debit(leg.amount)?,
+                            Side::Credit => balances.credit(leg.amount)?,
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(balances)
+    }
+
+    /// Current balances and stream version, folding only the events after the cached
+    /// running balances or, failing that, the latest stored snapshot.
+    fn account_state(&self, account_id: AccountId) -> Result<BalanceSnapshot, DomainError> {
+        let start = match self.states.get(&account_id) {
+            Some(state) => state.clone(),
+            None => self.store
+                .load_snapshot(account_id)?
+                .unwrap_or(BalanceSnapshot { account_id, balances: Balances::default(), version: 0 }),
+        };
+
+        let tail = self.store.read_stream_from(account_id, start.version)?;
+
+        if start.version == 0 && tail.is_empty() {
+            return Err(DomainError::AccountNotFound)
+        }
+
+        Ok(BalanceSnapshot {
+            account_id,
+            balances: Self::fold_balances(start.balances, tail.iter())?,
+            version: start.version + tail.len() as StreamVersion,
+        })
+    }
+
+    /// Append an event on top of `state`.
+    fn record(&mut self, state: BalanceSnapshot, event: LedgerEvent) -> Result<EventId, DomainError> {
+        let id = event.id;
+
+        self.commit(vec![(state, event)])?;
+
+        Ok(id)
+    }
+
+    /// Append events on top of their accounts' states, all or none. Returns the id of the first.
+    fn record_all(&mut self, states: Vec<BalanceSnapshot>, events: Vec<LedgerEvent>) -> Result<EventId, DomainError> {
+        let id = events[0].id;
+
+        self.commit(states.into_iter().zip(events).collect())?;
+
+        Ok(id)
+    }
+
+    /// Atomically append each event on top of its account's `state`, snapshotting any new
+    /// state that falls on the interval. An account with several events in the batch is
+    /// listed with the same state for each, and they are applied in order.
+    fn commit(&mut self, changes: Vec<(BalanceSnapshot, LedgerEvent)>) -> Result<(), DomainError> {
+        let mut expected_versions: Vec<(AccountId, StreamVersion)> = Vec::new();
+
+        for (state, _) in &changes {
+            if !expected_versions.iter().any(|(account_id, _)| *account_id == state.account_id) {
+                expected_versions.push((state.account_id, state.version));
+            }
+        }
+
+        let events: Vec<_> = changes.iter().map(|(_, event)| event.clone()).collect();
+
+        self.store
+            .append_all(&expected_versions, &events)
+            .map_err(|err| match err {
+                StoreError::VersionConflict { account_id, expected, actual } => {
+                    DomainError::ConcurrencyConflict { account_id, expected, actual }
+                }
+                other => DomainError::Storage(other),
+            })?;
+
+        // States as moved on by the events of this batch so far.
+        let mut moved: HashMap<AccountId, BalanceSnapshot> = HashMap::new();
+
+        for (state, event) in changes {
+            let state = moved.remove(&state.account_id).unwrap_or(state);
+
+            let next = BalanceSnapshot {
+                account_id: state.account_id,
+                balances: Self::fold_balances(state.balances, [&event])?,
+                version: state.version + 1,
+            };
+
+            // The event is already committed, so a failed snapshot only costs a longer replay later.
+            if next.version.is_multiple_of(self.snapshot_interval)
+                && let Err(err) = self.store.save_snapshot(&next)
+            {
+                warn!("Failed to snapshot account {}: {}", next.account_id, err);
+            }
+
+            self.states.insert(next.account_id, next.clone());
+            moved.insert(next.account_id, next);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::{Currency, Money, SignedMoney, events::LedgerEventPayload, fx::FxError, journal::JournalLeg}, store::SqliteEventStore};
+
+    #[test]
+    fn deposit_into_existing_account_appends_event() {
+        let mut ledger = Ledger::new();
+        let account_id = ledger.open_account(Currency::Gbp).unwrap();
+
+        let amount = Money::new_minor(10_00, Currency::Gbp).unwrap(); // £10
+        ledger.deposit(account_id, amount).unwrap();
+
+        let events = ledger.events_for_account(account_id).unwrap();
+        assert_eq!(events.len(), 2);
+
+        // Last event should be a Deposit
+        match &events.last().unwrap().payload {
+            LedgerEventPayload::Deposit { amount, .. } => {
+                assert_eq!(amount.amount(), 10_00);
+            }
+            other => panic!("expected Deposit event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn deposit_into_unknown_account_fails() {
+        let mut ledger = Ledger::new();
+        let fake_account = AccountId::new_v4();
+
+        let amount = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        let err = ledger.deposit(fake_account, amount).unwrap_err();
+
+        matches!(err, DomainError::AccountNotFound);
+    }
+
+    #[test]
+    fn withdrawal_reduces_balance_when_sufficient_funds() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+        let four = Money::new_minor(4_00, Currency::Gbp).unwrap();
+
+        ledger.deposit(account, ten).unwrap();
+        ledger.withdraw(account, four).unwrap();
+
+        let balance = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balance.get(Currency::Gbp).amount(), 6_00);
+    }
+
+    #[test]
+    fn withdrawal_fails_when_insufficient_funds() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+
+        ledger.deposit(account, five).unwrap();
+
+        let err = ledger.withdraw(account, ten).unwrap_err();
+        match err {
+            DomainError::InsufficientFunds {
+                required_minor,
+                available_minor,
+            } => {
+                assert_eq!(required_minor, 10_00);
+                assert_eq!(available_minor, 5_00);
+            }
+            other => panic!("expected InsufficientFunds, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn state_is_rebuilt_from_store_on_reopen() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+
+        let account = {
+            let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+            let account = ledger.open_account(Currency::Gbp).unwrap();
+
+            ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+            ledger.withdraw(account, Money::new_minor(3_00, Currency::Gbp).unwrap()).unwrap();
+
+            account
+        };
+
+        let ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+
+        assert_eq!(ledger.events_for_account(account).unwrap().len(), 3);
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 7_00);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn writers_sharing_a_store_cannot_both_spend_the_same_funds() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+        let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+        let mut other_writer = SqliteEventStore::open(&path).unwrap();
+
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        // Another writer reads version 2 and withdraws the full balance first...
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+        other_writer.append(account, 2, &[LedgerEvent::withdraw(account, ten)]).unwrap();
+
+        // ...so a withdrawal that was checked against version 2 must not land.
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+        balances.credit(ten).unwrap();
+        let stale = BalanceSnapshot { account_id: account, balances, version: 2 };
+        let err = ledger.record(stale, LedgerEvent::withdraw(account, ten)).unwrap_err();
+        assert!(matches!(err, DomainError::ConcurrencyConflict { expected: 2, actual: 3, .. }));
+
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 0);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_plus_tail_matches_full_replay() {
+        let mut ledger = Ledger::new().with_snapshot_interval(3);
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        for i in 1..=10 {
+            ledger.deposit(account, Money::new_minor(i * 1_00, Currency::Gbp).unwrap()).unwrap();
+            ledger.withdraw(account, Money::new_minor(50, Currency::Gbp).unwrap()).unwrap();
+        }
+
+        // 21 events, so the latest snapshot is at version 21 and covers everything.
+        let snapshot = ledger.store.load_snapshot(account).unwrap().unwrap();
+        assert_eq!(snapshot.version, 21);
+
+        ledger.deposit(account, Money::new_minor(1, Currency::Gbp).unwrap()).unwrap();
+
+        ledger.verify_snapshot(account).unwrap();
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 55_00 - 5_00 + 1);
+    }
+
+    #[test]
+    fn tampered_snapshot_fails_verification() {
+        let mut ledger = Ledger::new().with_snapshot_interval(2);
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+        balances.credit(Money::new_minor(99_00, Currency::Gbp).unwrap()).unwrap();
+        let forged = BalanceSnapshot { account_id: account, balances, version: 3 };
+        ledger.store.save_snapshot(&forged).unwrap();
+
+        let err = ledger.verify_snapshot(account).unwrap_err();
+        assert!(matches!(err, DomainError::SnapshotMismatch { .. }));
+    }
+
+    #[test]
+    fn snapshots_survive_reopen() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+
+        let account = {
+            let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap()).with_snapshot_interval(2);
+            let account = ledger.open_account(Currency::Gbp).unwrap();
+            ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+            ledger.deposit(account, Money::new_minor(2_00, Currency::Gbp).unwrap()).unwrap();
+            account
+        };
+
+        let ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+
+        let snapshot = ledger.store.load_snapshot(account).unwrap().unwrap();
+        assert_eq!(snapshot.version, 2);
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 12_00);
+        ledger.verify_snapshot(account).unwrap();
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn chain_verifies_across_accounts_and_reopen() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+
+        {
+            let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+            let first = ledger.open_account(Currency::Gbp).unwrap();
+            let second = ledger.open_account(Currency::Gbp).unwrap();
+            ledger.deposit(first, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+            ledger.deposit(second, Money::new_minor(20_00, Currency::Gbp).unwrap()).unwrap();
+        }
+
+        let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+        ledger.open_account(Currency::Gbp).unwrap();
+
+        // Three openings, the cash account's opening, and both sides of each deposit.
+        assert_eq!(ledger.verify_chain().unwrap(), (8, None));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn deposit_is_provably_included_under_the_published_root() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let deposit = ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.withdraw(account, Money::new_minor(1_00, Currency::Gbp).unwrap()).unwrap();
+
+        let (size, root) = ledger.merkle_root().unwrap();
+        let proof = ledger.inclusion_proof(deposit).unwrap();
+
+        // The account and cash account openings, then both sides of the deposit and withdrawal.
+        assert_eq!(size, 6);
+        assert_eq!(proof.root, root);
+        assert_eq!(proof.leaf_index, 2);
+        assert!(proof.verify());
+
+        assert!(matches!(ledger.inclusion_proof(EventId::new_v4()), Err(DomainError::EventNotFound)));
+    }
+
+    #[test]
+    fn balance_at_folds_only_events_up_to_the_point_in_time() {
+        use time::macros::datetime;
+
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let month_end = datetime!(2026-03-31 23:59:59 UTC);
+
+        let booked_at = |event: LedgerEvent, at| LedgerEvent { created_at: at, ..event };
+        ledger.store.append(account, 1, &[
+            booked_at(LedgerEvent::deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()), datetime!(2026-03-15 09:00 UTC)),
+            booked_at(LedgerEvent::withdraw(account, Money::new_minor(3_00, Currency::Gbp).unwrap()), month_end),
+            booked_at(LedgerEvent::deposit(account, Money::new_minor(5_00, Currency::Gbp).unwrap()), datetime!(2026-04-01 00:00 UTC)),
+        ]).unwrap();
+
+        assert_eq!(ledger.balance_at(account, AsOf::Time(month_end)).unwrap().get(Currency::Gbp).amount(), 7_00);
+        assert_eq!(ledger.balance_at(account, AsOf::Time(datetime!(2026-01-01 00:00 UTC))).unwrap().get(Currency::Gbp).amount(), 0);
+        assert_eq!(ledger.balance_at(account, AsOf::Version(2)).unwrap().get(Currency::Gbp).amount(), 10_00);
+        assert_eq!(ledger.balance_at(account, AsOf::Version(100)).unwrap().get(Currency::Gbp).amount(), 12_00);
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 12_00);
+    }
+
+    #[test]
+    fn value_dated_deposits_count_from_their_value_date_but_are_booked_now() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let before_booking = OffsetDateTime::now_utc();
+        let today = before_booking.date();
+        let three_days_ago = today - time::Duration::days(3);
+
+        let deposited = ledger.deposit_with_value_date(account, Money::new_minor(10_00, Currency::Gbp).unwrap(), Some(three_days_ago)).unwrap();
+
+        let event = ledger.events_for_account(account).unwrap().iter().find(|e| e.id == deposited).cloned().unwrap();
+        assert_eq!(event.value_date(), three_days_ago);
+        assert!(event.created_at >= before_booking);
+
+        let on = |date, known_at| ledger.balance_at(account, AsOf::ValueDate { date, known_at }).unwrap().get(Currency::Gbp).amount();
+        let now = OffsetDateTime::now_utc();
+
+        assert_eq!(on(three_days_ago, now), 10_00);
+        assert_eq!(on(three_days_ago - time::Duration::days(1), now), 0);
+        // Before it was booked the ledger didn't know about it, whatever day it counts from.
+        assert_eq!(on(today, before_booking), 0);
+        assert_eq!(ledger.balance_at(account, AsOf::Time(before_booking)).unwrap().get(Currency::Gbp).amount(), 0);
+    }
+
+    #[test]
+    fn value_dates_must_fall_within_the_window() {
+        use time::macros::{date, datetime};
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut ledger = Ledger::new()
+            .with_value_date_window(2 * day, Duration::ZERO)
+            .with_clock(|| datetime!(2026-03-10 23:59:59.999 +01:00));
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let today = date!(2026-03-10);
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+
+        let err = ledger.deposit_with_value_date(account, ten, Some(today - time::Duration::days(3))).unwrap_err();
+        assert!(matches!(err, DomainError::ValueDateOutOfRange { earliest, latest, .. } if earliest == today - time::Duration::days(2) && latest == today));
+        assert!(matches!(ledger.deposit_with_value_date(account, ten, today.next_day()), Err(DomainError::ValueDateOutOfRange { .. })));
+
+        ledger.deposit_with_value_date(account, ten, Some(today - time::Duration::days(2))).unwrap();
+        ledger.withdraw_with_value_date(account, ten, Some(today)).unwrap();
+        assert!(matches!(ledger.withdraw_with_value_date(account, ten, Some(today)), Err(DomainError::InsufficientFunds { .. })));
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 0);
+    }
+
+    #[test]
+    fn interest_accrues_on_balances_by_value_date() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let today = OffsetDateTime::now_utc().date();
+        let days_ago = |days| today - time::Duration::days(days);
+
+        ledger.deposit_with_value_date(account, Money::new_minor(100_000, Currency::Gbp).unwrap(), Some(days_ago(10))).unwrap();
+        ledger.withdraw_with_value_date(account, Money::new_minor(50_000, Currency::Gbp).unwrap(), Some(days_ago(5))).unwrap();
+
+        // 3.65% a year is 0.01% a day: five days on £1,000 and five on £500.
+        let interest = ledger.accrued_interest(account, Currency::Gbp, 365, days_ago(10), today).unwrap();
+        assert_eq!(interest.amount(), 75);
+
+        // Nothing was there before the deposit's value date, or in another currency.
+        assert_eq!(ledger.accrued_interest(account, Currency::Gbp, 365, days_ago(20), days_ago(10)).unwrap().amount(), 0);
+        assert_eq!(ledger.accrued_interest(account, Currency::Eur, 365, days_ago(10), today).unwrap().amount(), 0);
+    }
+
+    #[test]
+    fn transfer_moves_funds_and_links_both_legs() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let transfer_id = ledger.transfer(alice, bob, Money::new_minor(4_00, Currency::Gbp).unwrap()).unwrap();
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 6_00);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Gbp).amount(), 4_00);
+
+        match &ledger.events_for_account(alice).unwrap().last().unwrap().payload {
+            LedgerEventPayload::TransferDebit { transfer_id: id, counterparty, .. } => {
+                assert_eq!(*id, transfer_id);
+                assert_eq!(*counterparty, bob);
+            }
+            other => panic!("expected TransferDebit event, got {other:?}"),
+        }
+        match &ledger.events_for_account(bob).unwrap().last().unwrap().payload {
+            LedgerEventPayload::TransferCredit { transfer_id: id, counterparty, .. } => {
+                assert_eq!(*id, transfer_id);
+                assert_eq!(*counterparty, alice);
+            }
+            other => panic!("expected TransferCredit event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn transfer_without_funds_appends_nothing() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(1_00, Currency::Gbp).unwrap()).unwrap();
+
+        let err = ledger.transfer(alice, bob, Money::new_minor(5_00, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 5_00, available_minor: 1_00 }));
+
+        assert!(matches!(ledger.transfer(alice, alice, Money::new_minor(1, Currency::Gbp).unwrap()), Err(DomainError::SelfTransfer)));
+        assert!(matches!(ledger.transfer(alice, AccountId::new_v4(), Money::new_minor(1, Currency::Gbp).unwrap()), Err(DomainError::AccountNotFound)));
+
+        assert_eq!(ledger.events_for_account(alice).unwrap().len(), 2);
+        assert_eq!(ledger.events_for_account(bob).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn transfer_is_all_or_nothing_when_either_stream_moved() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+        let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+        let mut other_writer = SqliteEventStore::open(&path).unwrap();
+
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        // Someone else touches only the credit side after our states were read.
+        let alice_state = ledger.account_state(alice).unwrap();
+        let bob_state = ledger.account_state(bob).unwrap();
+        other_writer.append(bob, 1, &[LedgerEvent::deposit(bob, Money::new_minor(1, Currency::Gbp).unwrap())]).unwrap();
+
+        let (debit, credit) = LedgerEvent::transfer(TransferId::new_v4(), alice, bob, Money::new_minor(4_00, Currency::Gbp).unwrap(), None);
+        let err = ledger.commit(vec![(alice_state, debit), (bob_state, credit)]).unwrap_err();
+        assert!(matches!(err, DomainError::ConcurrencyConflict { account_id, .. } if account_id == bob));
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 10_00);
+        assert_eq!(ledger.events_for_account(alice).unwrap().len(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn account_holds_one_sub_balance_per_currency() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Eur).unwrap();
+
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.deposit(alice, Money::new_minor(20_00, Currency::Eur).unwrap()).unwrap();
+
+        // Plenty of pounds, but no dollars to cover this.
+        let err = ledger.withdraw(alice, Money::new_minor(1_00, Currency::Usd).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 1_00, available_minor: 0 }));
+
+        ledger.withdraw(alice, Money::new_minor(3_00, Currency::Eur).unwrap()).unwrap();
+        ledger.transfer(alice, bob, Money::new_minor(4_00, Currency::Gbp).unwrap()).unwrap();
+
+        let balances: Vec<_> = ledger.balance_for_account(alice).unwrap().iter().map(|b| (b.currency(), b.amount())).collect();
+        assert_eq!(balances, [(Currency::Eur, 17_00), (Currency::Gbp, 6_00)]);
+
+        let bob_balances = ledger.balance_for_account(bob).unwrap();
+        assert_eq!(bob_balances.get(Currency::Eur), SignedMoney::zero(Currency::Eur));
+        assert_eq!(bob_balances.get(Currency::Gbp).amount(), 4_00);
+
+        let earlier: Vec<_> = ledger.balance_at(alice, AsOf::Version(2)).unwrap().iter().map(|b| (b.currency(), b.amount())).collect();
+        assert_eq!(earlier, [(Currency::Gbp, 10_00)]);
+    }
+
+    #[test]
+    fn overdraft_lets_withdrawals_go_down_to_minus_the_limit() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        ledger.set_overdraft_limit(account, Money::new_minor(50_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.withdraw(account, Money::new_minor(40_00, Currency::Gbp).unwrap()).unwrap();
+
+        let balances = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balances.get(Currency::Gbp).amount(), -30_00);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 20_00);
+
+        let err = ledger.withdraw(account, Money::new_minor(20_01, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 20_01, available_minor: 20_00 }));
+
+        ledger.withdraw(account, Money::new_minor(20_00, Currency::Gbp).unwrap()).unwrap();
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), -50_00);
+
+        // An overdraft in pounds doesn't cover euros.
+        let err = ledger.withdraw(account, Money::new_minor(1, Currency::Eur).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { available_minor: 0, .. }));
+
+        ledger.verify_snapshot(account).unwrap();
+    }
+
+    #[test]
+    fn lowering_the_overdraft_limit_stops_further_debits() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        ledger.set_overdraft_limit(account, Money::new_minor(50_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.withdraw(account, Money::new_minor(30_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.set_overdraft_limit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let err = ledger.withdraw(account, Money::new_minor(1, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 1, available_minor: -20_00 }));
+
+        // Deposits are still fine, and the earlier limit still shows in history.
+        ledger.deposit(account, Money::new_minor(25_00, Currency::Gbp).unwrap()).unwrap();
+        assert_eq!(ledger.balance_for_account(account).unwrap().available(Currency::Gbp).unwrap().amount(), 5_00);
+        assert_eq!(ledger.balance_at(account, AsOf::Version(2)).unwrap().overdraft_limit(Currency::Gbp).amount(), 50_00);
+    }
+
+    fn test_rates() -> RateTable {
+        RateTable::from_json(r#"{
+            "source": "test desk",
+            "rates": [{ "from": "GBP", "to": "EUR", "rate": "1.1650" }]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn conversion_within_an_account_moves_between_sub_balances() {
+        let mut ledger = Ledger::new().with_rates(test_rates());
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(20_00, Currency::Gbp).unwrap()).unwrap();
+
+        let conversion = ledger.convert(account, account, Money::new_minor(10_01, Currency::Gbp).unwrap(), Currency::Eur).unwrap();
+        assert_eq!(conversion.target, Money::new_minor(11_66, Currency::Eur).unwrap());
+        assert_eq!(conversion.rate_source, "test desk");
+
+        let balances = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balances.get(Currency::Gbp).amount(), 9_99);
+        assert_eq!(balances.get(Currency::Eur).amount(), 11_66);
+
+        // A single event records both sides.
+        let events = ledger.events_for_account(account).unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2].payload, LedgerEventPayload::CurrencyConverted { .. }));
+    }
+
+    #[test]
+    fn conversion_across_accounts_debits_one_and_credits_the_other() {
+        let mut ledger = Ledger::new().with_rates(test_rates());
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Eur).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        ledger.convert(alice, bob, Money::new_minor(4_00, Currency::Gbp).unwrap(), Currency::Eur).unwrap();
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 6_00);
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Eur).amount(), 0);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Eur).amount(), 4_66);
+
+        ledger.verify_snapshot(alice).unwrap();
+        ledger.verify_snapshot(bob).unwrap();
+    }
+
+    #[test]
+    fn conversion_without_a_rate_or_funds_records_nothing() {
+        let mut ledger = Ledger::new().with_rates(test_rates());
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(1_00, Currency::Gbp).unwrap()).unwrap();
+
+        let err = ledger.convert(account, account, Money::new_minor(50, Currency::Gbp).unwrap(), Currency::Usd).unwrap_err();
+        assert!(matches!(err, DomainError::Fx(FxError::NoRate(Currency::Gbp, Currency::Usd))));
+
+        let err = ledger.convert(account, account, Money::new_minor(2_00, Currency::Gbp).unwrap(), Currency::Eur).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 2_00, available_minor: 1_00 }));
+
+        assert_eq!(ledger.events_for_account(account).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn holds_reserve_funds_until_captured_or_voided() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let hold = ledger.authorize(account, Money::new_minor(60_00, Currency::Gbp).unwrap()).unwrap();
+
+        let balances = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balances.get(Currency::Gbp).amount(), 10_000);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 40_00);
+
+        // Withdrawals and further holds can't touch the held funds.
+        let err = ledger.withdraw(account, Money::new_minor(50_00, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 50_00, available_minor: 40_00 }));
+        let err = ledger.authorize(account, Money::new_minor(40_01, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { .. }));
+
+        // A partial capture takes only what was captured and releases the rest.
+        ledger.capture(account, hold, Some(Money::new_minor(45_00, Currency::Gbp).unwrap())).unwrap();
+
+        let balances = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balances.get(Currency::Gbp).amount(), 55_00);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 55_00);
+
+        let err = ledger.capture(account, hold, None).unwrap_err();
+        assert!(matches!(err, DomainError::HoldNotFound));
+
+        let hold = ledger.authorize(account, Money::new_minor(5_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.void(account, hold).unwrap();
+        assert!(matches!(ledger.void(account, hold).unwrap_err(), DomainError::HoldNotFound));
+        assert_eq!(ledger.balance_for_account(account).unwrap().available(Currency::Gbp).unwrap().amount(), 55_00);
+
+        ledger.verify_snapshot(account).unwrap();
+    }
+
+    #[test]
+    fn capture_cannot_exceed_the_hold_or_change_its_currency() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+        let hold = ledger.authorize(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let err = ledger.capture(account, hold, Some(Money::new_minor(10_01, Currency::Gbp).unwrap())).unwrap_err();
+        assert!(matches!(err, DomainError::CaptureExceedsHold { held_minor: 10_00, requested_minor: 10_01 }));
+
+        let err = ledger.capture(account, hold, Some(Money::new_minor(1_00, Currency::Eur).unwrap())).unwrap_err();
+        assert!(matches!(err, DomainError::InvalidMoney(MoneyError::CurrencyMismatch(..))));
+
+        // A hold belongs to its account.
+        let other = ledger.open_account(Currency::Gbp).unwrap();
+        assert!(matches!(ledger.capture(other, hold, None).unwrap_err(), DomainError::HoldNotFound));
+
+        ledger.capture(account, hold, None).unwrap();
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 90_00);
+    }
+
+    #[test]
+    fn holds_expire_once_the_ttl_has_passed_since_authorization() {
+        let mut ledger = Ledger::new().with_hold_ttl(Duration::from_secs(60 * 60));
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let expiring = ledger.authorize(account, Money::new_minor(3_00, Currency::Gbp).unwrap()).unwrap();
+        let captured = ledger.authorize(account, Money::new_minor(2_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.capture(account, captured, None).unwrap();
+
+        let now = OffsetDateTime::now_utc();
+        assert_eq!(ledger.expire_holds(now).unwrap(), 0);
+
+        let later = now + Duration::from_secs(60 * 60);
+        assert_eq!(ledger.expire_holds(later).unwrap(), 1);
+        assert_eq!(ledger.expire_holds(later).unwrap(), 0);
+
+        let events = ledger.events_for_account(account).unwrap();
+        assert!(matches!(events.last().unwrap().payload, LedgerEventPayload::HoldExpired { hold_id } if hold_id == expiring));
+
+        let balances = ledger.balance_for_account(account).unwrap();
+        assert_eq!(balances.get(Currency::Gbp).amount(), 8_00);
+        assert_eq!(balances.available(Currency::Gbp).unwrap().amount(), 8_00);
+    }
+
+    #[test]
+    fn holds_never_expire_when_the_ttl_runs_past_the_end_of_time() {
+        // Far enough to run past the last date `time` can represent.
+        let mut ledger = Ledger::new().with_hold_ttl(Duration::from_secs(u64::from(u32::MAX) * 1_000));
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.authorize(account, Money::new_minor(3_00, Currency::Gbp).unwrap()).unwrap();
+
+        assert_eq!(ledger.expire_holds(OffsetDateTime::now_utc()).unwrap(), 0);
+
+        // Too long to be a `time::Duration` at all.
+        ledger = ledger.with_hold_ttl(Duration::MAX);
+        assert_eq!(ledger.expire_holds(OffsetDateTime::now_utc()).unwrap(), 0);
+        assert_eq!(ledger.balance_for_account(account).unwrap().held(Currency::Gbp).unwrap().amount(), 3_00);
+    }
+
+    #[test]
+    fn missed_scheduled_runs_are_made_once_each_and_failures_recorded() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let (account, later) = {
+            let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+            let account = ledger.open_account(Currency::Gbp).unwrap();
+            ledger.deposit(account, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+            let starts_at = OffsetDateTime::now_utc() + Duration::from_secs(60);
+            let payment = ScheduledPayment::Withdrawal { amount: Money::new_minor(30_00, Currency::Gbp).unwrap() };
+            ledger.schedule_payment(account, payment, Frequency::Daily, Some(starts_at)).unwrap();
+
+            assert_eq!(ledger.run_due_payments(OffsetDateTime::now_utc()).unwrap(), 0);
+
+            // Down for three and a bit days: four runs are due, the last without the funds.
+            let later = starts_at + 3 * day + Duration::from_secs(60 * 60);
+            assert_eq!(ledger.run_due_payments(later).unwrap(), 4);
+            assert_eq!(ledger.run_due_payments(later).unwrap(), 0);
+
+            (account, later)
+        };
+
+        // Nothing is made twice after a restart either.
+        let mut ledger = Ledger::with_store(SqliteEventStore::open(&path).unwrap());
+        assert_eq!(ledger.run_due_payments(later).unwrap(), 0);
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 10_00);
+
+        let events = ledger.events_for_account(account).unwrap();
+        let made: Vec<_> = events.iter().filter_map(|e| match e.payload {
+            LedgerEventPayload::ScheduledPaymentMade { payment_id, .. } => Some(payment_id),
+            _ => None,
+        }).collect();
+        assert_eq!(made.len(), 3);
+        assert!(made.iter().all(|id| events.iter().any(|e| e.id == *id && matches!(e.payload, LedgerEventPayload::Withdrawal { .. }))));
+
+        let LedgerEventPayload::ScheduledPaymentFailed { due_at, ref reason, .. } = events.last().unwrap().payload else {
+            panic!("expected the last run to fail, got {:?}", events.last().unwrap().payload)
+        };
+        assert!(due_at <= later && due_at + day > later);
+        assert!(reason.starts_with("insufficient funds"));
+
+        assert_eq!(ledger.verify_chain().unwrap().1, None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn late_runs_count_from_the_day_they_fell_due() {
+        use time::macros::{date, datetime};
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut ledger = Ledger::new()
+            .with_value_date_window(day, Duration::ZERO)
+            .with_clock(|| datetime!(2026-03-01 08:00 UTC));
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let payment = ScheduledPayment::Transfer { to: bob, amount: Money::new_minor(10_00, Currency::Gbp).unwrap() };
+        ledger.schedule_payment(alice, payment, Frequency::Daily, Some(datetime!(2026-03-01 09:00 UTC))).unwrap();
+
+        // The late runs count from the days they fell due, even the one further back than
+        // callers may date, and the run due today from when it was made.
+        let mut ledger = ledger.with_clock(|| datetime!(2026-03-03 12:00 UTC));
+        assert_eq!(ledger.run_due_payments(datetime!(2026-03-03 12:00 UTC)).unwrap(), 3);
+
+        let events = ledger.events_for_account(bob).unwrap();
+        let credits: Vec<_> = events.iter().filter_map(|e| match e.payload {
+            LedgerEventPayload::TransferCredit { value_date, .. } => Some(value_date),
+            _ => None,
+        }).collect();
+        assert_eq!(credits, vec![Some(date!(2026-03-01)), Some(date!(2026-03-02)), None]);
+        assert!(ledger.trial_balance().unwrap().is_balanced());
+    }
+
+    #[test]
+    fn runs_missed_for_longer_than_the_backdate_window_are_still_made() {
+        use time::macros::{date, datetime};
+
+        let mut ledger = Ledger::new().with_clock(|| datetime!(2026-03-01 08:00 UTC));
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let payment = ScheduledPayment::Transfer { to: bob, amount: Money::new_minor(1_00, Currency::Gbp).unwrap() };
+        ledger.schedule_payment(alice, payment, Frequency::Daily, Some(datetime!(2026-03-01 09:00 UTC))).unwrap();
+
+        // Down for 40 days, past the default 30 day backdate window: every run is still made once.
+        let now = datetime!(2026-04-09 12:00 UTC);
+        let mut ledger = ledger.with_clock(|| datetime!(2026-04-09 12:00 UTC));
+        assert_eq!(ledger.run_due_payments(now).unwrap(), 40);
+        assert_eq!(ledger.run_due_payments(now).unwrap(), 0);
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 60_00);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Gbp).amount(), 40_00);
+
+        let events = ledger.events_for_account(alice).unwrap();
+        assert!(!events.iter().any(|e| matches!(e.payload, LedgerEventPayload::ScheduledPaymentFailed { .. })));
+        let first = events.iter().find_map(|e| match e.payload {
+            LedgerEventPayload::TransferDebit { value_date, .. } => Some(value_date),
+            _ => None,
+        });
+        assert_eq!(first, Some(Some(date!(2026-03-01))));
+    }
+
+    #[test]
+    fn scheduled_transfers_can_be_paused_resumed_and_cancelled() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+        let weekly = ScheduledPayment::Transfer { to: bob, amount: ten };
+
+        assert!(matches!(ledger.schedule_payment(alice, ScheduledPayment::Transfer { to: alice, amount: ten }, Frequency::Weekly, None), Err(DomainError::SelfTransfer)));
+        let yesterday = OffsetDateTime::now_utc() - Duration::from_secs(24 * 60 * 60);
+        assert!(matches!(ledger.schedule_payment(alice, weekly, Frequency::Weekly, Some(yesterday)), Err(DomainError::InvalidSchedule(_))));
+
+        let schedule = ledger.schedule_payment(alice, weekly, Frequency::Weekly, None).unwrap();
+
+        // The first run fell due before the pause, so is still made once resumed.
+        ledger.pause_schedule(schedule).unwrap();
+        assert!(matches!(ledger.pause_schedule(schedule), Err(DomainError::SchedulePaused)));
+        assert_eq!(ledger.run_due_payments(OffsetDateTime::now_utc()).unwrap(), 0);
+
+        ledger.resume_schedule(schedule).unwrap();
+        assert!(matches!(ledger.resume_schedule(schedule), Err(DomainError::ScheduleNotPaused)));
+        assert_eq!(ledger.run_due_payments(OffsetDateTime::now_utc()).unwrap(), 1);
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 90_00);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Gbp).amount(), 10_00);
+
+        ledger.cancel_schedule(schedule).unwrap();
+        assert!(matches!(ledger.cancel_schedule(schedule), Err(DomainError::ScheduleCancelled)));
+        assert!(matches!(ledger.resume_schedule(schedule), Err(DomainError::ScheduleCancelled)));
+        assert_eq!(ledger.run_due_payments(OffsetDateTime::now_utc() + Duration::from_secs(30 * 24 * 60 * 60)).unwrap(), 0);
+
+        assert!(matches!(ledger.pause_schedule(ScheduleId::new_v4()), Err(DomainError::ScheduleNotFound)));
+    }
+
+    #[test]
+    fn schedules_stop_once_an_account_they_need_is_closed() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        let carol = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_000, Currency::Gbp).unwrap()).unwrap();
+
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+        let to_bob = ledger.schedule_payment(alice, ScheduledPayment::Transfer { to: bob, amount: ten }, Frequency::Weekly, None).unwrap();
+        let from_carol = ledger.schedule_payment(carol, ScheduledPayment::Withdrawal { amount: ten }, Frequency::Daily, None).unwrap();
+
+        // Closing the paying account cancels its schedules along with it.
+        ledger.close(carol).unwrap();
+        assert_eq!(ledger.schedule(from_carol).unwrap().status, ScheduleStatus::Cancelled);
+        let payloads: Vec<_> = ledger.events_for_account(carol).unwrap().iter().rev().take(2).map(|e| e.payload.clone()).collect();
+        assert!(matches!(payloads[..], [LedgerEventPayload::AccountClosed, LedgerEventPayload::ScheduleCancelled { .. }]));
+
+        // A closed payee fails the run, and cancels the schedule rather than failing every week.
+        ledger.close(bob).unwrap();
+        let in_two_weeks = OffsetDateTime::now_utc() + Duration::from_secs(14 * 24 * 60 * 60);
+        assert_eq!(ledger.run_due_payments(in_two_weeks).unwrap(), 1);
+        assert_eq!(ledger.schedule(to_bob).unwrap().status, ScheduleStatus::Cancelled);
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 10_000);
+
+        let payloads: Vec<_> = ledger.events_for_account(alice).unwrap().iter().rev().take(2).map(|e| e.payload.clone()).collect();
+        assert!(matches!(payloads[..], [LedgerEventPayload::ScheduleCancelled { .. }, LedgerEventPayload::ScheduledPaymentFailed { .. }]));
+    }
+
+    #[test]
+    fn frozen_accounts_refuse_debits_and_optionally_credits() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let other = ledger.open_account(Currency::Gbp).unwrap();
+        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        ledger.deposit(account, five).unwrap();
+        ledger.deposit(other, five).unwrap();
+
+        ledger.freeze(account, false).unwrap();
+        assert_eq!(ledger.account_status(account).unwrap(), AccountStatus::Frozen { allow_credits: false });
+
+        assert!(matches!(ledger.withdraw(account, five).unwrap_err(), DomainError::AccountFrozen));
+        assert!(matches!(ledger.authorize(account, five).unwrap_err(), DomainError::AccountFrozen));
+        assert!(matches!(ledger.deposit(account, five).unwrap_err(), DomainError::AccountFrozen));
+        assert!(matches!(ledger.transfer(other, account, five).unwrap_err(), DomainError::AccountFrozen));
+
+        ledger.freeze(account, true).unwrap();
+        ledger.deposit(account, five).unwrap();
+        ledger.transfer(other, account, five).unwrap();
+        assert!(matches!(ledger.transfer(account, other, five).unwrap_err(), DomainError::AccountFrozen));
+
+        ledger.unfreeze(account).unwrap();
+        assert!(matches!(ledger.unfreeze(account).unwrap_err(), DomainError::AccountNotFrozen));
+        ledger.withdraw(account, five).unwrap();
+
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 10_00);
+        ledger.verify_snapshot(account).unwrap();
+    }
+
+    #[test]
+    fn accounts_close_only_when_settled_and_stay_closed() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        ledger.deposit(account, five).unwrap();
+
+        assert!(matches!(ledger.close(account).unwrap_err(), DomainError::AccountNotSettled));
+
+        let hold = ledger.authorize(account, five).unwrap();
+        ledger.capture(account, hold, None).unwrap();
+
+        // A zero balance in another currency doesn't stand in the way.
+        ledger.deposit(account, Money::new_minor(1, Currency::Eur).unwrap()).unwrap();
+        ledger.withdraw(account, Money::new_minor(1, Currency::Eur).unwrap()).unwrap();
+
+        ledger.close(account).unwrap();
+        assert_eq!(ledger.account_status(account).unwrap(), AccountStatus::Closed);
+
+        assert!(matches!(ledger.deposit(account, five).unwrap_err(), DomainError::AccountClosed));
+        assert!(matches!(ledger.withdraw(account, five).unwrap_err(), DomainError::AccountClosed));
+        assert!(matches!(ledger.freeze(account, true).unwrap_err(), DomainError::AccountClosed));
+        assert!(matches!(ledger.unfreeze(account).unwrap_err(), DomainError::AccountClosed));
+        assert!(matches!(ledger.close(account).unwrap_err(), DomainError::AccountClosed));
+        assert!(matches!(ledger.set_overdraft_limit(account, five).unwrap_err(), DomainError::AccountClosed));
+
+        // History still reads as before.
+        assert_eq!(ledger.balance_at(account, AsOf::Version(2)).unwrap().status(), AccountStatus::Open);
+    }
+
+    #[test]
+    fn details_are_recorded_on_opening_and_updated_by_events() {
+        let mut ledger = Ledger::new();
+        let details = AccountDetails {
+            owner_ref: Some("cust-42".to_string()),
+            display_name: Some("Savings".to_string()),
+            labels: ["retail".to_string()].into(),
+            external_id: Some("ext-1".to_string()),
+            ..Default::default()
+        };
+        let account = ledger.open_account_with_details(Currency::Gbp, details.clone()).unwrap();
+
+        assert_eq!(ledger.account_details(account).unwrap(), details);
+        assert_eq!(ledger.find_by_external_id("ext-1").unwrap(), account);
+
+        ledger.update_details(account, AccountDetailsChanges {
+            display_name: Some("Rainy day".to_string()),
+            external_id: Some("ext-2".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let updated = ledger.account_details(account).unwrap();
+        assert_eq!(updated.owner_ref.as_deref(), Some("cust-42"));
+        assert_eq!(updated.display_name.as_deref(), Some("Rainy day"));
+        assert_eq!(ledger.find_by_external_id("ext-2").unwrap(), account);
+        assert!(matches!(ledger.find_by_external_id("ext-1").unwrap_err(), DomainError::AccountNotFound));
+
+        // Details have no bearing on the balance.
+        assert_eq!(ledger.balance_for_account(account).unwrap(), Balances::opened_in(Currency::Gbp, AccountType::Liability));
+    }
+
+    #[test]
+    fn external_ids_are_unique() {
+        let mut ledger = Ledger::new();
+        let with_id = |id: &str| AccountDetails { external_id: Some(id.to_string()), ..Default::default() };
+
+        let first = ledger.open_account_with_details(Currency::Gbp, with_id("ext-1")).unwrap();
+        let err = ledger.open_account_with_details(Currency::Gbp, with_id("ext-1")).unwrap_err();
+        assert!(matches!(err, DomainError::ExternalIdTaken(id) if id == "ext-1"));
+
+        let second = ledger.open_account(Currency::Gbp).unwrap();
+        let take_first = AccountDetailsChanges { external_id: Some("ext-1".to_string()), ..Default::default() };
+        assert!(matches!(ledger.update_details(second, take_first.clone()).unwrap_err(), DomainError::ExternalIdTaken(_)));
+
+        // Setting an account's own id again is fine, and clearing it frees it up.
+        ledger.update_details(first, take_first.clone()).unwrap();
+        ledger.update_details(first, AccountDetailsChanges { external_id: Some(String::new()), ..Default::default() }).unwrap();
+        ledger.update_details(second, take_first).unwrap();
+        assert_eq!(ledger.find_by_external_id("ext-1").unwrap(), second);
+    }
+
+    #[test]
+    fn every_operation_keeps_the_trial_balance_balanced() {
+        let mut ledger = Ledger::new().with_rates(test_rates());
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+
+        ledger.deposit(alice, Money::new_minor(50_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.withdraw(alice, Money::new_minor(5_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.transfer(alice, bob, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.convert(alice, bob, Money::new_minor(10_01, Currency::Gbp).unwrap(), Currency::Eur).unwrap();
+        let hold = ledger.authorize(bob, Money::new_minor(3_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.capture(bob, hold, Some(Money::new_minor(2_00, Currency::Gbp).unwrap())).unwrap();
+
+        let trial_balance = ledger.trial_balance().unwrap();
+        assert!(trial_balance.is_balanced());
+
+        let gbp = |minor| Money::new_minor(minor, Currency::Gbp).unwrap();
+        let eur = |minor| Money::new_minor(minor, Currency::Eur).unwrap();
+        // Customers and the FX position hold what came in through cash: £50 less £5 withdrawn and £2 captured.
+        assert_eq!(trial_balance.totals[&Currency::Gbp], (gbp(43_00), gbp(43_00)));
+        assert_eq!(trial_balance.totals[&Currency::Eur], (eur(11_66), eur(11_66)));
+
+        // Cash in bank mirrors what customers hold, so sits in the debit column.
+        let cash = trial_balance.lines.iter().find(|line| line.account_id == SystemAccount::CashInBank.id()).unwrap();
+        assert_eq!((cash.debit, cash.credit), (gbp(43_00), gbp(0)));
+
+        for account in [alice, bob, SystemAccount::CashInBank.id(), SystemAccount::FxPosition.id()] {
+            ledger.verify_snapshot(account).unwrap();
+        }
+    }
+
+    #[test]
+    fn journal_entries_post_to_every_account_or_none() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+
+        let suspense = SystemAccount::Suspense.id();
+        let six = Money::new_minor(6_00, Currency::Gbp).unwrap();
+        let four = Money::new_minor(4_00, Currency::Gbp).unwrap();
+
+        // Alice pays £10: £6 to Bob and £4 parked in suspense.
+        let entry = JournalEntry::new(vec![
+            JournalLeg::debit(alice, six.checked_add(four).unwrap()),
+            JournalLeg::credit(bob, six),
+            JournalLeg::credit(suspense, four),
+        ], Some("split payment".to_string())).unwrap();
+        ledger.post_entry(entry).unwrap();
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 0);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Gbp).amount(), 6_00);
+        assert_eq!(ledger.balance_for_account(suspense).unwrap().get(Currency::Gbp).amount(), 4_00);
+
+        // Alice has nothing left to pay with, so nothing is posted to Bob either.
+        let entry = JournalEntry::new(vec![JournalLeg::debit(alice, four), JournalLeg::credit(bob, four)], None).unwrap();
+        assert!(matches!(ledger.post_entry(entry).unwrap_err(), DomainError::InsufficientFunds { .. }));
+        assert_eq!(ledger.events_for_account(bob).unwrap().len(), 2);
+
+        // System accounts need no funds.
+        let entry = JournalEntry::new(vec![JournalLeg::debit(suspense, six), JournalLeg::credit(bob, six)], None).unwrap();
+        ledger.post_entry(entry).unwrap();
+        assert_eq!(ledger.balance_for_account(suspense).unwrap().get(Currency::Gbp).amount(), -2_00);
+
+        assert!(ledger.trial_balance().unwrap().is_balanced());
+    }
+
+    #[test]
+    fn system_accounts_only_move_through_entries() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let cash = SystemAccount::CashInBank.id();
+        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        ledger.deposit(account, five).unwrap();
+
+        assert!(matches!(ledger.deposit(cash, five).unwrap_err(), DomainError::SystemAccount));
+        assert!(matches!(ledger.transfer(cash, account, five).unwrap_err(), DomainError::SystemAccount));
+        assert!(matches!(ledger.freeze(cash, false).unwrap_err(), DomainError::SystemAccount));
+    }
+
+    #[test]
+    fn single_sided_events_from_before_double_entry_unbalance_the_trial_balance() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        let state = ledger.account_state(account).unwrap();
+        ledger.record(state, LedgerEvent::deposit(account, Money::new_minor(1_00, Currency::Gbp).unwrap())).unwrap();
+
+        assert!(!ledger.trial_balance().unwrap().is_balanced());
+    }
+
+    fn open_in_chart(ledger: &mut Ledger, account_type: AccountType, code: &str, name: &str) -> Result<AccountId, DomainError> {
+        let details = AccountDetails { code: Some(code.to_string()), display_name: Some(name.to_string()), ..Default::default() };
+        ledger.open_account_of_type(Currency::Gbp, account_type, details)
+    }
+
+    fn post(ledger: &mut Ledger, debit: AccountId, credit: AccountId, amount_minor: i64) {
+        let amount = Money::new_minor(amount_minor, Currency::Gbp).unwrap();
+        ledger.post_entry(JournalEntry::new(vec![JournalLeg::debit(debit, amount), JournalLeg::credit(credit, amount)], None).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn account_types_decide_which_side_adds_to_the_balance() {
+        let mut ledger = Ledger::new();
+        let bank = open_in_chart(&mut ledger, AccountType::Asset, "1000", "Bank").unwrap();
+        let sales = open_in_chart(&mut ledger, AccountType::Income, "4000", "Sales").unwrap();
+        let rent = open_in_chart(&mut ledger, AccountType::Expense, "6000", "Rent").unwrap();
+
+        post(&mut ledger, bank, sales, 80_00);
+        post(&mut ledger, rent, bank, 30_00);
+
+        let balance = |ledger: &Ledger, account| ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount();
+        assert_eq!(balance(&ledger, bank), 50_00);
+        assert_eq!(balance(&ledger, sales), 80_00);
+        assert_eq!(balance(&ledger, rent), 30_00);
+
+        // Cash in bank is an asset too, so customer deposits add to it.
+        let customer = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(customer, Money::new_minor(5_00, Currency::Gbp).unwrap()).unwrap();
+        assert_eq!(balance(&ledger, SystemAccount::CashInBank.id()), 5_00);
+        assert_eq!(ledger.account_type(customer).unwrap(), AccountType::Liability);
+
+        // Only customer wallets take deposits, withdrawals and the like.
+        let err = ledger.deposit(rent, Money::new_minor(1_00, Currency::Gbp).unwrap()).unwrap_err();
+        assert!(matches!(err, DomainError::JournalOnly(AccountType::Expense)));
+
+        assert!(ledger.trial_balance().unwrap().is_balanced());
+        for account in [bank, sales, rent, SystemAccount::CashInBank.id()] {
+            ledger.verify_snapshot(account).unwrap();
+        }
+    }
+
+    #[test]
+    fn codes_are_unique_and_parents_share_the_type() {
+        let mut ledger = Ledger::new();
+        open_in_chart(&mut ledger, AccountType::Expense, "6000", "Premises").unwrap();
+
+        let with_parent = |code: &str, parent_code: &str| AccountDetails {
+            code: Some(code.to_string()),
+            parent_code: Some(parent_code.to_string()),
+            ..Default::default()
+        };
+
+        let rent = ledger.open_account_of_type(Currency::Gbp, AccountType::Expense, with_parent("6100", "6000")).unwrap();
+        assert_eq!(ledger.account_details(rent).unwrap().parent_code.as_deref(), Some("6000"));
+
+        let err = open_in_chart(&mut ledger, AccountType::Income, "6000", "Duplicate").unwrap_err();
+        assert!(matches!(err, DomainError::AccountCodeTaken(code) if code == "6000"));
+
+        let err = ledger.open_account_of_type(Currency::Gbp, AccountType::Income, with_parent("4100", "6000")).unwrap_err();
+        assert!(matches!(err, DomainError::InvalidParent(_)));
+
+        let err = ledger.open_account_of_type(Currency::Gbp, AccountType::Expense, with_parent("6200", "9999")).unwrap_err();
+        assert!(matches!(err, DomainError::InvalidParent(_)));
+    }
+
+    #[test]
+    fn statements_cover_the_requested_period() {
+        use time::macros::datetime;
+
+        let mut ledger = Ledger::new();
+        let bank = open_in_chart(&mut ledger, AccountType::Asset, "1000", "Bank").unwrap();
+        let capital = open_in_chart(&mut ledger, AccountType::Equity, "3000", "Share capital").unwrap();
+        let sales = open_in_chart(&mut ledger, AccountType::Income, "4000", "Sales").unwrap();
+        let rent = open_in_chart(&mut ledger, AccountType::Expense, "6000", "Rent").unwrap();
+        let customer = ledger.open_account(Currency::Gbp).unwrap();
+
+        post(&mut ledger, bank, capital, 100_000);
+        post(&mut ledger, bank, sales, 20_000);
+        ledger.deposit(customer, Money::new_minor(25_00, Currency::Gbp).unwrap()).unwrap();
+        let before_period = ledger.store.read_all_from(0).unwrap().len();
+
+        post(&mut ledger, bank, sales, 30_000);
+        post(&mut ledger, rent, bank, 12_000);
+        let during_period = ledger.store.read_all_from(0).unwrap().len();
+
+        // After the period, so on neither statement.
+        post(&mut ledger, bank, sales, 5_000);
+
+        // Book each part at a fixed time on either side of the period.
+        let (from, to) = (datetime!(2026-04-01 00:00 UTC), datetime!(2026-06-30 23:59:59 UTC));
+        let events: Vec<_> = ledger.store.read_all_from(0).unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(position, event)| {
+                let created_at = match position {
+                    position if position < before_period => datetime!(2026-03-15 09:00 UTC),
+                    position if position < during_period => datetime!(2026-05-15 09:00 UTC),
+                    _ => datetime!(2026-07-15 09:00 UTC),
+                };
+                LedgerEvent { created_at, ..event }
+            })
+            .collect();
+
+        let mut store = InMemoryEventStore::new();
+        store.append_all(&[], &events).unwrap();
+        let ledger = Ledger::with_store(store);
+
+        let statement = ledger.income_statement(from, to).unwrap();
+        assert_eq!(statement.income.len(), 1);
+        assert_eq!(statement.income[0].code.as_deref(), Some("4000"));
+        assert_eq!(statement.income[0].amount.amount(), 30_000);
+        assert_eq!(statement.expenses[0].name.as_deref(), Some("Rent"));
+        assert_eq!(statement.expenses[0].amount.amount(), 12_000);
+        assert_eq!(statement.net_income().unwrap()[&Currency::Gbp].amount(), 18_000);
+
+        let sheet = ledger.balance_sheet(from, to).unwrap();
+        assert!(sheet.is_balanced().unwrap());
+
+        // Coded accounts first, then the rest.
+        let assets: Vec<_> = sheet.assets.iter().map(|line| (line.account_id, line.amount.amount())).collect();
+        assert_eq!(assets, vec![(bank, 138_000), (SystemAccount::CashInBank.id(), 25_00)]);
+        assert_eq!(sheet.liabilities[0].account_id, customer);
+        assert_eq!(sheet.equity[0].amount.amount(), 100_000);
+        assert_eq!(sheet.retained_earnings[&Currency::Gbp].amount(), 20_000);
+        assert_eq!(sheet.net_income[&Currency::Gbp].amount(), 18_000);
+    }
+
+    #[test]
+    fn reversing_a_deposit_undoes_it_on_both_sides_once() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let cash = SystemAccount::CashInBank.id();
+        let deposit = ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        let cash_copy = ledger.events_for_account(cash).unwrap().last().unwrap().id;
+
+        let reversal = ledger.reverse(deposit, "keyed in twice").unwrap();
+
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), 0);
+        assert_eq!(ledger.balance_for_account(cash).unwrap().get(Currency::Gbp).amount(), 0);
+        assert!(ledger.trial_balance().unwrap().is_balanced());
+
+        // Each side names the original event on its own account.
+        let reverses = |ledger: &Ledger, account| match &ledger.events_for_account(account).unwrap().last().unwrap().payload {
+            LedgerEventPayload::EventReversed { reverses, reason, .. } => (*reverses, reason.clone()),
+            other => panic!("expected a reversal, got {other:?}"),
+        };
+        assert_eq!(reverses(&ledger, account), (deposit, "keyed in twice".to_string()));
+        assert_eq!(reverses(&ledger, cash).0, cash_copy);
+
+        assert!(matches!(ledger.reverse(deposit, "again").unwrap_err(), DomainError::AlreadyReversed(id) if id == reversal));
+        assert!(matches!(ledger.reverse(cash_copy, "other side").unwrap_err(), DomainError::NotReversible(_)));
+        assert!(matches!(ledger.reverse(reversal, "undo the undo").unwrap_err(), DomainError::NotReversible(_)));
+
+        let opened = ledger.events_for_account(account).unwrap()[0].id;
+        assert!(matches!(ledger.reverse(opened, "no money").unwrap_err(), DomainError::NotReversible(_)));
+        assert!(matches!(ledger.reverse(EventId::new_v4(), "unknown").unwrap_err(), DomainError::EventNotFound));
+
+        for account in [account, cash] {
+            ledger.verify_snapshot(account).unwrap();
+        }
+    }
+
+    #[test]
+    fn reversal_needs_the_funds_it_takes_back() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+        let deposit = ledger.deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.withdraw(account, Money::new_minor(8_00, Currency::Gbp).unwrap()).unwrap();
+
+        let err = ledger.reverse(deposit, "chargeback").unwrap_err();
+        assert!(matches!(err, DomainError::InsufficientFunds { required_minor: 10_00, available_minor: 2_00 }));
+        assert_eq!(ledger.events_for_account(account).unwrap().len(), 3);
+
+        ledger.set_overdraft_limit(account, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.reverse(deposit, "chargeback").unwrap();
+        assert_eq!(ledger.balance_for_account(account).unwrap().get(Currency::Gbp).amount(), -8_00);
+    }
+
+    #[test]
+    fn either_leg_of_a_transfer_reverses_the_whole_transfer() {
+        let mut ledger = Ledger::new();
+        let alice = ledger.open_account(Currency::Gbp).unwrap();
+        let bob = ledger.open_account(Currency::Gbp).unwrap();
+        ledger.deposit(alice, Money::new_minor(10_00, Currency::Gbp).unwrap()).unwrap();
+        ledger.transfer(alice, bob, Money::new_minor(4_00, Currency::Gbp).unwrap()).unwrap();
+
+        let debit = ledger.events_for_account(alice).unwrap().last().unwrap().id;
+        let credit = ledger.events_for_account(bob).unwrap().last().unwrap().id;
+
+        ledger.reverse(credit, "wrong payee").unwrap();
+
+        assert_eq!(ledger.balance_for_account(alice).unwrap().get(Currency::Gbp).amount(), 10_00);
+        assert_eq!(ledger.balance_for_account(bob).unwrap().get(Currency::Gbp).amount(), 0);
+        assert!(matches!(ledger.reverse(debit, "wrong payee").unwrap_err(), DomainError::AlreadyReversed(_)));
+    }
+
+    #[test]
+    fn movements_from_before_double_entry_are_not_reversed() {
+        let mut ledger = Ledger::new();
+        let account = ledger.open_account(Currency::Gbp).unwrap();
+
+        let legacy = LedgerEvent::deposit(account, Money::new_minor(10_00, Currency::Gbp).unwrap());
+        ledger.store.append(account, 1, std::slice::from_ref(&legacy)).unwrap();
+
+        assert!(matches!(ledger.reverse(legacy.id, "keyed in twice").unwrap_err(), DomainError::NotReversible(_)));
+        assert_eq!(ledger.events_for_account(account).unwrap().len(), 2);
+        assert!(ledger.events_for_account(SystemAccount::CashInBank.id()).is_err());
+    }
+
+    /// Rough benchmark showing per-operation cost stays flat as the log grows.
+    /// Run with `just bench`.
+    #[test]
+    #[ignore]
+    fn bench_operations_up_to_a_million_events() {
+        use std::time::Instant;
+
+        const ACCOUNTS: usize = 10_000;
+        const EVENTS: usize = 1_000_000;
+        const REPORT_EVERY: usize = 100_000;
+
+        let mut ledger = Ledger::new();
+        let accounts: Vec<_> = (0..ACCOUNTS).map(|_| ledger.open_account(Currency::Gbp).unwrap()).collect();
+        let penny = Money::new_minor(1, Currency::Gbp).unwrap();
+
+        println!("{:>10} {:>14}", "events", "deposit (ns)");
+
+        let mut started = Instant::now();
+        for i in ACCOUNTS..EVENTS {
+            ledger.deposit(accounts[i % ACCOUNTS], penny).unwrap();
+
+            if (i + 1) % REPORT_EVERY == 0 {
+                let per_op = started.elapsed().as_nanos() / REPORT_EVERY as u128;
+                println!("{:>10} {:>14}", i + 1, per_op);
+                started = Instant::now();
+            }
+        }
+
+        let time_per_account = |label: &str, op: &mut dyn FnMut(AccountId)| {
+            let started = Instant::now();
+            for account in &accounts {
+                op(*account);
+            }
+            println!("{label:>25}: {} ns", started.elapsed().as_nanos() / ACCOUNTS as u128);
+        };
+
+        time_per_account("balance_for_account", &mut |account| {
+            ledger.balance_for_account(account).unwrap();
+        });
+        time_per_account("events_for_account", &mut |account| {
+            ledger.events_for_account(account).unwrap();
+        });
+        time_per_account("withdraw", &mut |account| {
+            ledger.withdraw(account, penny).unwrap();
+        });
+
+        // Every account and the cash account opened, then both sides of every deposit and withdrawal.
+        assert_eq!(ledger.store.read_all_from(0).unwrap().len(), 2 * EVENTS + ACCOUNTS + 1);
     }
 }
@@ -0,0 +1,223 @@
+//! Locale conventions for showing and reading amounts of money.
+//!
+//! A locale decides the thousands and decimal separators and which side of the
+//! number the currency symbol goes. The number of decimal places always comes
+//! from the currency, never the locale.
+
+use std::str::FromStr;
+
+use crate::domain::{Currency, Money, MoneyError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// `£1,234.56`
+    #[default]
+    EnGb,
+    /// `$1,234.56`
+    EnUs,
+    /// `1.234,56 €`
+    DeDe,
+    /// `1 234,56 €`, grouped with a narrow no-break space.
+    FrFr,
+}
+
+impl Locale {
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::EnGb => "en-GB",
+            Locale::EnUs => "en-US",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+        }
+    }
+
+    fn group_separator(&self) -> char {
+        match self {
+            Locale::EnGb | Locale::EnUs => ',',
+            Locale::DeDe => '.',
+            Locale::FrFr => '\u{202f}',
+        }
+    }
+
+    fn decimal_separator(&self) -> char {
+        match self {
+            Locale::EnGb | Locale::EnUs => '.',
+            Locale::DeDe | Locale::FrFr => ',',
+        }
+    }
+
+    fn symbol_after(&self) -> bool {
+        matches!(self, Locale::DeDe | Locale::FrFr)
+    }
+
+    /// Whether `c` separates thousands. French is also commonly written with an
+    /// ordinary or no-break space, so those are accepted when parsing.
+    fn is_group_separator(&self, c: char) -> bool {
+        c == self.group_separator() || (*self == Locale::FrFr && matches!(c, ' ' | '\u{a0}'))
+    }
+
+    /// Format an amount of minor units, e.g. `-£1,234.50` or `1.234,50 €`.
+    pub fn format_minor(&self, amount: i64, currency: Currency) -> String {
+        let sign = if amount < 0 { "-" } else { "" };
+
+        let magnitude = amount.unsigned_abs();
+        let exponent = currency.exponent();
+        let divisor = 10_u64.pow(exponent);
+
+        let mut number = group_digits(&(magnitude / divisor).to_string(), self.group_separator());
+
+        if exponent > 0 {
+            number.push(self.decimal_separator());
+            number.push_str(&format!("{:0width$}", magnitude % divisor, width = exponent as usize));
+        }
+
+        let symbol = currency.symbol();
+
+        match (self.symbol_after(), symbol == currency.code()) {
+            (true, _) => format!("{sign}{number} {symbol}"),
+            (false, true) => format!("{sign}{symbol} {number}"),
+            (false, false) => format!("{sign}{symbol}{number}"),
+        }
+    }
+
+    /// Parse an amount written in this locale, optionally marked with the currency's
+    /// symbol or code on either side: `1,234.56`, `£1,234.56`, `1.234,56 €`, `EUR 12`.
+    ///
+    /// More decimal places than the currency allows is an error, as is a symbol or
+    /// code for a different currency.
+    pub fn parse(&self, input: &str, currency: Currency) -> Result<Money, MoneyError> {
+        let invalid = || MoneyError::InvalidAmount(input.to_string());
+
+        let number = strip_marker(input.trim(), currency.code())
+            .or_else(|| strip_marker(input.trim(), currency.symbol()))
+            .unwrap_or(input.trim());
+
+        let (whole, fraction) = match number.split_once(self.decimal_separator()) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (number, None),
+        };
+
+        if let Some(fraction) = fraction
+            && fraction.len() > currency.exponent() as usize
+            && fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(MoneyError::InvalidAmount(format!(
+                "{input} has more than {} decimal places for {}",
+                currency.exponent(),
+                currency.code()
+            )));
+        }
+
+        let whole = ungroup_digits(whole, |c| self.is_group_separator(c)).ok_or_else(invalid)?;
+
+        let decimal = match fraction {
+            Some(fraction) => format!("{whole}.{fraction}"),
+            None => whole,
+        };
+
+        // Report the input as given, not our normalised form of it.
+        Money::from_decimal(&decimal, currency).map_err(|err| match err {
+            MoneyError::InvalidAmount(_) => invalid(),
+            other => other,
+        })
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Locale::EnGb, Locale::EnUs, Locale::DeDe, Locale::FrFr]
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unsupported locale: {s}"))
+    }
+}
+
+/// Take the currency marker off either end of `input`, along with any space next to it.
+fn strip_marker<'a>(input: &'a str, marker: &str) -> Option<&'a str> {
+    input
+        .strip_prefix(marker)
+        .or_else(|| input.strip_suffix(marker))
+        .map(|rest| rest.trim_matches(|c: char| c.is_whitespace() || c == '\u{202f}'))
+}
+
+fn group_digits(digits: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// Remove thousands separators, which must sit between groups of exactly three digits.
+fn ungroup_digits(whole: &str, is_separator: impl Fn(char) -> bool) -> Option<String> {
+    let groups: Vec<_> = whole.split(is_separator).collect();
+
+    if let [first, rest @ ..] = groups.as_slice() {
+        let well_formed = rest.is_empty() || (!first.is_empty() && first.len() <= 3 && rest.iter().all(|g| g.len() == 3));
+
+        if !well_formed {
+            return None;
+        }
+    }
+
+    Some(groups.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_with_locale_separators_and_symbol_placement() {
+        assert_eq!(Locale::EnGb.format_minor(123_456, Currency::Gbp), "£1,234.56");
+        assert_eq!(Locale::DeDe.format_minor(123_456, Currency::Eur), "1.234,56 €");
+        assert_eq!(Locale::FrFr.format_minor(123_456_789, Currency::Eur), "1\u{202f}234\u{202f}567,89 €");
+        assert_eq!(Locale::EnUs.format_minor(-100_000_000, Currency::Usd), "-$1,000,000.00");
+        assert_eq!(Locale::EnGb.format_minor(1_234_567, Currency::Jpy), "¥1,234,567");
+        assert_eq!(Locale::EnGb.format_minor(1_250, Currency::Kwd), "KWD 1.250");
+        assert_eq!(Locale::EnGb.format_minor(5, Currency::Gbp), "£0.05");
+    }
+
+    #[test]
+    fn parses_amounts_with_and_without_markers() {
+        let gbp = |minor| Money::new_minor(minor, Currency::Gbp).unwrap();
+
+        assert_eq!(Locale::EnGb.parse("12.34", Currency::Gbp), Ok(gbp(12_34)));
+        assert_eq!(Locale::EnGb.parse("£1,234.56", Currency::Gbp), Ok(gbp(123_456)));
+        assert_eq!(Locale::EnGb.parse("GBP 1234.5", Currency::Gbp), Ok(gbp(123_450)));
+        assert_eq!(Locale::EnGb.parse(" 7 GBP ", Currency::Gbp), Ok(gbp(7_00)));
+
+        let eur = Money::new_minor(123_456, Currency::Eur).unwrap();
+        assert_eq!(Locale::DeDe.parse("1.234,56 €", Currency::Eur), Ok(eur));
+        assert_eq!(Locale::FrFr.parse("1 234,56 €", Currency::Eur), Ok(eur));
+    }
+
+    #[test]
+    fn rejects_excess_precision_and_malformed_amounts() {
+        let err = Locale::EnGb.parse("£1.234", Currency::Gbp).unwrap_err();
+        assert_eq!(err, MoneyError::InvalidAmount("£1.234 has more than 2 decimal places for GBP".to_string()));
+
+        assert!(Locale::EnGb.parse("¥10.5", Currency::Jpy).is_err());
+
+        for invalid in ["", "£", "1,23.00", ",123", "1,2345", "€12", "-1.00", "12.3.4", "1.234,56"] {
+            assert!(Locale::EnGb.parse(invalid, Currency::Gbp).is_err(), "{invalid} should be rejected");
+        }
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        for locale in [Locale::EnGb, Locale::EnUs, Locale::DeDe, Locale::FrFr] {
+            for (minor, currency) in [(0, Currency::Eur), (987_654_321, Currency::Gbp), (1_234_567, Currency::Jpy), (1_005, Currency::Bhd)] {
+                let formatted = locale.format_minor(minor, currency);
+                assert_eq!(locale.parse(&formatted, currency), Ok(Money::new_minor(minor, currency).unwrap()), "{formatted}");
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Merkle tree over the ledger's events, following the RFC 6962 (Certificate
+//! Transparency) layout so proofs can be checked with off-the-shelf tooling:
+//!
+//! - leaf hash: `SHA-256(0x00 || event.hash)`
+//! - node hash: `SHA-256(0x01 || left || right)`
+//! - a tree of `n` leaves splits at the largest power of two below `n`.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::domain::chain::EventHash;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Which side of the running hash a proof step's sibling sits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ProofStep {
+    pub side: Side,
+    pub hash: EventHash,
+}
+
+/// Everything needed to check offline that an event is included under `root`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub tree_size: u64,
+    pub event_hash: EventHash,
+    /// Siblings from the leaf up to the root.
+    pub path: Vec<ProofStep>,
+    pub root: EventHash,
+}
+
+pub fn leaf_hash(event_hash: &EventHash) -> EventHash {
+    digest(&[&[LEAF_PREFIX], event_hash.as_bytes()])
+}
+
+fn node_hash(left: &EventHash, right: &EventHash) -> EventHash {
+    digest(&[&[NODE_PREFIX], left.as_bytes(), right.as_bytes()])
+}
+
+fn digest(parts: &[&[u8]]) -> EventHash {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    EventHash::from(<[u8; 32]>::from(hasher.finalize()))
+}
+
+/// Largest power of two strictly less than `n` (for `n > 1`).
+fn split_point(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+/// Root over the given event hashes, in log order.
+pub fn root(event_hashes: &[EventHash]) -> EventHash {
+    match event_hashes {
+        [] => digest(&[]),
+        [single] => leaf_hash(single),
+        _ => {
+            let k = split_point(event_hashes.len());
+            node_hash(&root(&event_hashes[..k]), &root(&event_hashes[k..]))
+        }
+    }
+}
+
+/// Inclusion proof for the event at `index`, or `None` if it's out of range.
+pub fn inclusion_proof(event_hashes: &[EventHash], index: usize) -> Option<InclusionProof> {
+    let event_hash = *event_hashes.get(index)?;
+
+    let mut path = Vec::new();
+    collect_path(event_hashes, index, &mut path);
+
+    Some(InclusionProof {
+        leaf_index: index as u64,
+        tree_size: event_hashes.len() as u64,
+        event_hash,
+        path,
+        root: root(event_hashes),
+    })
+}
+
+fn collect_path(event_hashes: &[EventHash], index: usize, path: &mut Vec<ProofStep>) {
+    if event_hashes.len() <= 1 {
+        return;
+    }
+
+    let k = split_point(event_hashes.len());
+
+    if index < k {
+        collect_path(&event_hashes[..k], index, path);
+        path.push(ProofStep { side: Side::Right, hash: root(&event_hashes[k..]) });
+    } else {
+        collect_path(&event_hashes[k..], index - k, path);
+        path.push(ProofStep { side: Side::Left, hash: root(&event_hashes[..k]) });
+    }
+}
+
+impl InclusionProof {
+    /// Recompute the root from the event hash and path, as an offline verifier would.
+    pub fn verify(&self) -> bool {
+        let computed = self.path.iter().fold(leaf_hash(&self.event_hash), |acc, step| match step.side {
+            Side::Left => node_hash(&step.hash, &acc),
+            Side::Right => node_hash(&acc, &step.hash),
+        });
+
+        computed == self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(n: u8) -> Vec<EventHash> {
+        (0..n).map(|i| EventHash::from([i; 32])).collect()
+    }
+
+    #[test]
+    fn split_point_is_largest_power_of_two_below_n() {
+        assert_eq!(split_point(2), 1);
+        assert_eq!(split_point(3), 2);
+        assert_eq!(split_point(4), 2);
+        assert_eq!(split_point(5), 4);
+        assert_eq!(split_point(8), 4);
+        assert_eq!(split_point(9), 8);
+    }
+
+    #[test]
+    fn every_leaf_proves_against_the_root_for_any_tree_size() {
+        for size in 1..=17 {
+            let leaves = hashes(size);
+            let expected_root = root(&leaves);
+
+            for index in 0..leaves.len() {
+                let proof = inclusion_proof(&leaves, index).unwrap();
+                assert_eq!(proof.root, expected_root);
+                assert!(proof.verify(), "leaf {index} of {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn proof_for_a_different_event_does_not_verify() {
+        let leaves = hashes(6);
+        let mut proof = inclusion_proof(&leaves, 2).unwrap();
+
+        proof.event_hash = leaves[3];
+        assert!(!proof.verify());
+    }
+
+    #[test]
+    fn three_leaf_tree_matches_hand_computed_root() {
+        let leaves = hashes(3);
+
+        let left = node_hash(&leaf_hash(&leaves[0]), &leaf_hash(&leaves[1]));
+        assert_eq!(root(&leaves), node_hash(&left, &leaf_hash(&leaves[2])));
+
+        assert!(inclusion_proof(&leaves, 3).is_none());
+    }
+}
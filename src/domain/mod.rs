@@ -1,7 +1,20 @@
+pub mod currency;
 pub mod money;
+pub mod balances;
+pub mod chain;
+pub mod chart;
+pub mod details;
 pub mod events;
+pub mod fx;
+pub mod journal;
+pub mod locale;
 pub mod ledger;
+pub mod merkle;
+pub mod schedule;
+pub mod snapshot;
+pub mod status;
 pub mod types;
 pub mod errors;
 
-pub use money::{Currency, Money, MoneyError};
\ No newline at end of file
+pub use currency::Currency;
+pub use money::{Money, MoneyError, SignedMoney};
\ No newline at end of file
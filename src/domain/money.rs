@@ -1,32 +1,9 @@
 use core::fmt;
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum Currency {
-    Gbp,
-    Usd,
-    Eur,
-}
-
-impl Currency {
-    pub fn code(&self) -> &'static str {
-        match self {
-            Currency::Eur => "EUR",
-            Currency::Gbp => "GBP",
-            Currency::Usd => "USD"
-        }
-    }
-
-    pub fn symbol(&self) -> &'static str {
-        match self {
-            Currency::Eur => "€",
-            Currency::Gbp => "£",
-            Currency::Usd => "$"
-        }
-    }
-}
+use crate::domain::{Currency, locale::Locale};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Money {
@@ -42,6 +19,18 @@ pub enum MoneyError {
 
     #[error("cannot operate on different currencies: {0:?} vs {1:?}")]
     CurrencyMismatch(Currency, Currency),
+
+    #[error("unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+
+    #[error("invalid amount: {0}")]
+    InvalidAmount(String),
+
+    #[error("amount out of range")]
+    Overflow,
+
+    #[error("cannot allocate by ratios that are empty or all zero")]
+    InvalidRatios,
 }
 
 impl Money {
@@ -53,6 +42,35 @@ impl Money {
         Ok(Self { amount, currency })
     }
 
+    /// Parse a plain decimal amount in major units, such as `12.34` for GBP or `1500`
+    /// for JPY. More decimal places than the currency's exponent is an error rather
+    /// than being rounded away.
+    pub fn from_decimal(amount: &str, currency: Currency) -> Result<Self, MoneyError> {
+        let invalid = || MoneyError::InvalidAmount(amount.to_string());
+
+        let (whole, fraction) = match amount.split_once('.') {
+            Some((_, "")) => return Err(invalid()),
+            Some(parts) => parts,
+            None => (amount, ""),
+        };
+
+        if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let exponent = currency.exponent() as usize;
+        if fraction.len() > exponent {
+            return Err(MoneyError::InvalidAmount(format!(
+                "{amount} has more than {exponent} decimal places for {}",
+                currency.code()
+            )));
+        }
+
+        let minor = format!("{whole}{fraction:0<exponent$}");
+
+        Self::new_minor(minor.parse().map_err(|_| invalid())?, currency)
+    }
+
     pub fn zero(currency: Currency) -> Self {
         Self { amount: 0, currency }
     }
@@ -65,12 +83,17 @@ impl Money {
         self.currency
     }
 
+    /// Format for display in `locale`, e.g. `£1,234.56` or `1.234,56 €`.
+    pub fn format(&self, locale: Locale) -> String {
+        locale.format_minor(self.amount, self.currency)
+    }
+
     pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
         if self.currency != other.currency {
             return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
         }
         Ok(Self {
-            amount: self.amount + other.amount,
+            amount: self.amount.checked_add(other.amount).ok_or(MoneyError::Overflow)?,
             currency: self.currency,
         })
     }
@@ -83,27 +106,149 @@ impl Money {
             return Err(MoneyError::Negative(self.amount - other.amount));
         }
         Ok(Self {
-            amount: self.amount - other.amount,
+            amount: self.amount.checked_sub(other.amount).ok_or(MoneyError::Overflow)?,
             currency: self.currency,
         })
     }
+
+    /// Divide into shares proportional to `ratios` that always add back up to exactly
+    /// this amount.
+    ///
+    /// Each share is first rounded down. The minor units left over go one each to the
+    /// shares that lost the largest fractions, earlier shares first on a tie, so the
+    /// result is deterministic and a share never gets more than a unit above its exact value.
+    pub fn allocate(self, ratios: &[u64]) -> Result<Vec<Money>, MoneyError> {
+        let total: u128 = ratios.iter().map(|&r| u128::from(r)).sum();
+
+        if total == 0 {
+            return Err(MoneyError::InvalidRatios);
+        }
+
+        let amount = u128::from(self.amount.unsigned_abs());
+
+        let mut shares: Vec<u128> = Vec::with_capacity(ratios.len());
+        let mut fractions: Vec<(u128, usize)> = Vec::with_capacity(ratios.len());
+
+        for (i, &ratio) in ratios.iter().enumerate() {
+            let exact = amount * u128::from(ratio);
+            shares.push(exact / total);
+            fractions.push((exact % total, i));
+        }
+
+        let leftover = amount - shares.iter().sum::<u128>();
+
+        // Largest fraction first, then lowest index.
+        fractions.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        for &(_, i) in fractions.iter().take(leftover as usize) {
+            shares[i] += 1;
+        }
+
+        // No share exceeds the original amount, so each fits back in an i64.
+        shares
+            .into_iter()
+            .map(|share| Self::new_minor(i64::try_from(share).map_err(|_| MoneyError::Overflow)?, self.currency))
+            .collect()
+    }
+
+    /// Divide into `n` shares as equal as possible, e.g. £10 into £3.34, £3.33, £3.33.
+    pub fn split(self, n: usize) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![1; n])
+    }
 }
 
-impl fmt::Display for Money {
+/// A signed amount in minor units, for balances that may be overdrawn.
+///
+/// Unlike [`Money`] this can go below zero. Movements are still made in `Money`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedMoney {
+    amount: i64,
+    currency: Currency,
+}
+
+impl SignedMoney {
+    pub fn zero(currency: Currency) -> Self {
+        Self { amount: 0, currency }
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount < 0
+    }
+
+    pub fn checked_add(self, other: impl Into<SignedMoney>) -> Result<SignedMoney, MoneyError> {
+        let other = other.into();
+
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        Ok(Self {
+            amount: self.amount.checked_add(other.amount).ok_or(MoneyError::Overflow)?,
+            currency: self.currency,
+        })
+    }
+
+    pub fn checked_sub(self, other: impl Into<SignedMoney>) -> Result<SignedMoney, MoneyError> {
+        let other = other.into();
+
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        Ok(Self {
+            amount: self.amount.checked_sub(other.amount).ok_or(MoneyError::Overflow)?,
+            currency: self.currency,
+        })
+    }
+
+    /// Format for display in `locale`, e.g. `-£1,234.56`.
+    pub fn format(&self, locale: Locale) -> String {
+        locale.format_minor(self.amount, self.currency)
+    }
+}
+
+impl From<Money> for SignedMoney {
+    fn from(money: Money) -> Self {
+        Self { amount: money.amount, currency: money.currency }
+    }
+}
+
+impl fmt::Display for SignedMoney {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let digits = 2;
+        write!(f, "{}", self.currency.format_minor(self.amount))
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    /// Parse an amount marked with its currency's code or symbol on either side,
+    /// written the en-GB way: `£1,234.56`, `12.34 EUR`, `JPY 1500`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+
+        // Longest marker wins, so `CA$12` is Canadian rather than US dollars.
+        let currency = Currency::ALL
+            .iter()
+            .flat_map(|c| [(*c, c.code()), (*c, c.symbol())])
+            .filter(|(_, marker)| trimmed.starts_with(marker) || trimmed.ends_with(marker))
+            .max_by_key(|(_, marker)| marker.len())
+            .map(|(currency, _)| currency)
+            .ok_or_else(|| MoneyError::InvalidAmount(format!("{s} does not name a currency")))?;
 
-        let divisor = 10_i64.pow(digits);
-        let major = self.amount / divisor;
-        let minor = self.amount % divisor;
+        Locale::EnGb.parse(s, currency)
+    }
+}
 
-        write!(
-            f,
-            "{}{}.{:02}",
-            self.currency.symbol(),
-            major,
-            minor
-        )
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.currency.format_minor(self.amount))
     }
 }
 
@@ -156,6 +301,73 @@ mod tests {
         assert!(matches!(result, Err(MoneyError::Negative(_))));
     }
 
+    #[test]
+    fn checked_add_overflow_is_an_error() {
+        let max = Money::new_minor(i64::MAX, Currency::Gbp).unwrap();
+        let one = Money::new_minor(1, Currency::Gbp).unwrap();
+
+        assert_eq!(max.checked_add(one), Err(MoneyError::Overflow));
+        assert_eq!(max.checked_sub(one).unwrap().amount(), i64::MAX - 1);
+    }
+
+    #[test]
+    fn split_sums_back_to_the_original() {
+        let ten = Money::new_minor(10_00, Currency::Gbp).unwrap();
+
+        let shares: Vec<_> = ten.split(3).unwrap().iter().map(Money::amount).collect();
+        assert_eq!(shares, [3_34, 3_33, 3_33]);
+
+        assert_eq!(ten.split(0), Err(MoneyError::InvalidRatios));
+    }
+
+    #[test]
+    fn allocate_gives_leftovers_to_the_largest_fractions() {
+        let amount = Money::new_minor(1_00, Currency::Gbp).unwrap();
+
+        // Exact shares are 16.67, 33.33 and 50.
+        let shares: Vec<_> = amount.allocate(&[1, 2, 3]).unwrap().iter().map(Money::amount).collect();
+        assert_eq!(shares, [17, 33, 50]);
+
+        let shares: Vec<_> = amount.allocate(&[0, 1, 0, 1]).unwrap().iter().map(Money::amount).collect();
+        assert_eq!(shares, [0, 50, 0, 50]);
+
+        assert_eq!(amount.allocate(&[]), Err(MoneyError::InvalidRatios));
+        assert_eq!(amount.allocate(&[0, 0]), Err(MoneyError::InvalidRatios));
+    }
+
+    #[test]
+    fn allocate_never_loses_or_invents_a_unit() {
+        for minor in [0, 1, 7, 99_99, 1_000_003, i64::MAX] {
+            let amount = Money::new_minor(minor, Currency::Eur).unwrap();
+
+            for ratios in [&[1, 1, 1][..], &[3, 7], &[u64::MAX, 1], &[5, 0, 11, 2, 9]] {
+                let shares = amount.allocate(ratios).unwrap();
+                let total = shares.iter().try_fold(Money::zero(Currency::Eur), |sum, share| sum.checked_add(*share)).unwrap();
+                assert_eq!(total, amount, "{minor} by {ratios:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn signed_money_goes_below_zero() {
+        let five = Money::new_minor(5_00, Currency::Gbp).unwrap();
+
+        let overdrawn = SignedMoney::zero(Currency::Gbp).checked_sub(five).unwrap();
+        assert!(overdrawn.is_negative());
+        assert_eq!(overdrawn.to_string(), "-£5.00");
+        assert_eq!(overdrawn.checked_add(five).unwrap(), SignedMoney::zero(Currency::Gbp));
+
+        let min = SignedMoney { amount: i64::MIN, currency: Currency::Gbp };
+        assert_eq!(min.checked_sub(Money::new_minor(1, Currency::Gbp).unwrap()), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn currency_parses_from_code() {
+        assert_eq!("EUR".parse::<Currency>(), Ok(Currency::Eur));
+        assert_eq!("USD".parse::<Currency>(), Ok(Currency::Usd));
+        assert_eq!("gbp".parse::<Currency>(), Err(MoneyError::UnsupportedCurrency("gbp".to_string())));
+    }
+
     #[test]
     fn display_formats_gbp_properly() {
         let m = Money::new_minor(3400, Currency::Gbp).unwrap();
@@ -180,6 +392,41 @@ mod tests {
         assert_eq!(m.to_string(), "€123.45");
     }
 
+    #[test]
+    fn display_honours_the_currency_exponent() {
+        assert_eq!(Money::new_minor(1500, Currency::Jpy).unwrap().to_string(), "¥1500");
+        assert_eq!(Money::new_minor(1_005, Currency::Bhd).unwrap().to_string(), "BHD 1.005");
+    }
+
+    #[test]
+    fn from_decimal_honours_the_currency_exponent() {
+        assert_eq!(Money::from_decimal("12.34", Currency::Gbp).unwrap().amount(), 12_34);
+        assert_eq!(Money::from_decimal("12.3", Currency::Gbp).unwrap().amount(), 12_30);
+        assert_eq!(Money::from_decimal("12", Currency::Gbp).unwrap().amount(), 12_00);
+        assert_eq!(Money::from_decimal("1500", Currency::Jpy).unwrap().amount(), 1500);
+        assert_eq!(Money::from_decimal("1.005", Currency::Kwd).unwrap().amount(), 1_005);
+
+        assert!(matches!(Money::from_decimal("1.005", Currency::Gbp), Err(MoneyError::InvalidAmount(_))));
+        assert!(matches!(Money::from_decimal("1.5", Currency::Jpy), Err(MoneyError::InvalidAmount(_))));
+        assert!(matches!(Money::from_decimal("92233720368547758.08", Currency::Usd), Err(MoneyError::InvalidAmount(_))));
+
+        for invalid in ["", "1.", ".5", "-1", "1,000", "£1"] {
+            assert!(Money::from_decimal(invalid, Currency::Gbp).is_err(), "{invalid} should be rejected");
+        }
+    }
+
+    #[test]
+    fn parses_amounts_marked_with_their_currency() {
+        assert_eq!("£1,234.56".parse::<Money>(), Ok(Money::new_minor(123_456, Currency::Gbp).unwrap()));
+        assert_eq!("12.34 EUR".parse::<Money>(), Ok(Money::new_minor(12_34, Currency::Eur).unwrap()));
+        assert_eq!("CA$5".parse::<Money>(), Ok(Money::new_minor(5_00, Currency::Cad).unwrap()));
+        assert_eq!("$5".parse::<Money>(), Ok(Money::new_minor(5_00, Currency::Usd).unwrap()));
+        assert_eq!("JPY 1,500".parse::<Money>(), Ok(Money::new_minor(1500, Currency::Jpy).unwrap()));
+
+        assert!(matches!("12.34".parse::<Money>(), Err(MoneyError::InvalidAmount(_))));
+        assert!(matches!("£12.345".parse::<Money>(), Err(MoneyError::InvalidAmount(_))));
+    }
+
     #[test]
     fn display_formats_euro_zero() {
         let m = Money::new_minor(0, Currency::Eur).unwrap();
@@ -0,0 +1,167 @@
+//! Standing orders: a payment taken from an account again and again on a fixed calendar.
+//!
+//! A schedule is only ever described by events on the paying account's stream. Each run
+//! that falls due is recorded as made or failed in the same append as the payment itself,
+//! so however long the ledger was down, every missed run is made once and only once.
+
+use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
+
+use crate::domain::{Money, types::{AccountId, ScheduleId}};
+
+/// What each run of a schedule takes out of the paying account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduledPayment {
+    Withdrawal { amount: Money },
+    Transfer { to: AccountId, amount: Money },
+}
+
+impl ScheduledPayment {
+    pub fn amount(&self) -> Money {
+        match self {
+            ScheduledPayment::Withdrawal { amount } | ScheduledPayment::Transfer { amount, .. } => *amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    /// On the same day of every month as the first run, or the month's last day if it is shorter.
+    Monthly,
+}
+
+impl Frequency {
+    /// When the `n`th run after the first falls due, or `None` if that is past the end of time.
+    pub fn occurrence(&self, starts_at: OffsetDateTime, n: u32) -> Option<OffsetDateTime> {
+        match self {
+            Frequency::Daily => starts_at.checked_add(time::Duration::days(i64::from(n))),
+            Frequency::Weekly => starts_at.checked_add(time::Duration::weeks(i64::from(n))),
+            Frequency::Monthly => {
+                // Counted from the first run, so a run clamped to a short month doesn't pull the rest earlier.
+                let months = i64::from(u8::from(starts_at.month()) - 1) + i64::from(n);
+                let year = i32::try_from(i64::from(starts_at.year()) + months / 12).ok()?;
+                let month = Month::try_from(u8::try_from(months % 12 + 1).ok()?).ok()?;
+                let day = starts_at.day().min(month.length(year));
+
+                Some(starts_at.replace_date(Date::from_calendar_date(year, month, day).ok()?))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleStatus {
+    Active,
+    /// No runs are made. Those falling due from `since` until it is resumed are skipped.
+    Paused { since: OffsetDateTime },
+    /// Cancelled for good.
+    Cancelled,
+}
+
+/// A standing order as of the events recorded for it so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub schedule_id: ScheduleId,
+    pub account_id: AccountId,
+    pub payment: ScheduledPayment,
+    pub frequency: Frequency,
+    pub starts_at: OffsetDateTime,
+    pub status: ScheduleStatus,
+    /// Occurrences before this one have been made or failed.
+    next_run: u32,
+    /// From when to when it was paused. Runs falling due in these windows are skipped.
+    paused: Vec<(OffsetDateTime, OffsetDateTime)>,
+}
+
+impl Schedule {
+    pub fn new(schedule_id: ScheduleId, account_id: AccountId, payment: ScheduledPayment, frequency: Frequency, starts_at: OffsetDateTime) -> Self {
+        Self { schedule_id, account_id, payment, frequency, starts_at, status: ScheduleStatus::Active, next_run: 0, paused: Vec::new() }
+    }
+
+    /// When the next run falls due, or `None` while paused and once cancelled.
+    pub fn next_run_at(&self) -> Option<OffsetDateTime> {
+        match self.status {
+            ScheduleStatus::Active => self.next_occurrence().map(|(_, due_at)| due_at),
+            ScheduleStatus::Paused { .. } | ScheduleStatus::Cancelled => None,
+        }
+    }
+
+    /// The next run was made or failed.
+    pub fn ran(&mut self) {
+        if let Some((n, _)) = self.next_occurrence() {
+            self.next_run = n + 1;
+        }
+    }
+
+    /// The first occurrence from `next_run` on that didn't fall due while paused.
+    fn next_occurrence(&self) -> Option<(u32, OffsetDateTime)> {
+        (self.next_run..)
+            .map_while(|n| Some((n, self.frequency.occurrence(self.starts_at, n)?)))
+            .find(|(_, due_at)| !self.paused.iter().any(|(from, until)| from <= due_at && due_at < until))
+    }
+
+    pub fn pause(&mut self, at: OffsetDateTime) {
+        self.status = ScheduleStatus::Paused { since: at };
+    }
+
+    /// Make runs again from `at`, skipping those that fell due while paused. Runs that were
+    /// already due before the pause are still made.
+    pub fn resume(&mut self, at: OffsetDateTime) {
+        if let ScheduleStatus::Paused { since } = self.status {
+            self.paused.push((since, at));
+        }
+
+        self.status = ScheduleStatus::Active;
+    }
+
+    pub fn cancel(&mut self) {
+        self.status = ScheduleStatus::Cancelled;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    use crate::domain::Currency;
+
+    fn schedule(frequency: Frequency, starts_at: OffsetDateTime) -> Schedule {
+        let payment = ScheduledPayment::Withdrawal { amount: Money::new_minor(50_00, Currency::Gbp).unwrap() };
+        Schedule::new(ScheduleId::new_v4(), AccountId::new_v4(), payment, frequency, starts_at)
+    }
+
+    #[test]
+    fn monthly_runs_keep_their_day_and_fit_short_months() {
+        let starts_at = datetime!(2026-01-31 09:00 UTC);
+
+        assert_eq!(Frequency::Monthly.occurrence(starts_at, 1), Some(datetime!(2026-02-28 09:00 UTC)));
+        assert_eq!(Frequency::Monthly.occurrence(starts_at, 2), Some(datetime!(2026-03-31 09:00 UTC)));
+        assert_eq!(Frequency::Monthly.occurrence(starts_at, 13), Some(datetime!(2027-02-28 09:00 UTC)));
+        assert_eq!(Frequency::Weekly.occurrence(starts_at, 2), Some(datetime!(2026-02-14 09:00 UTC)));
+        assert_eq!(Frequency::Daily.occurrence(starts_at, 1), Some(datetime!(2026-02-01 09:00 UTC)));
+    }
+
+    #[test]
+    fn resuming_skips_only_the_runs_due_while_paused() {
+        let mut schedule = schedule(Frequency::Daily, datetime!(2026-03-01 09:00 UTC));
+        schedule.ran();
+
+        // 2 March was missed before the pause, 3 to 5 March fell due while paused.
+        schedule.pause(datetime!(2026-03-02 12:00 UTC));
+        assert_eq!(schedule.next_run_at(), None);
+
+        schedule.resume(datetime!(2026-03-05 12:00 UTC));
+        assert_eq!(schedule.next_run_at(), Some(datetime!(2026-03-02 09:00 UTC)));
+
+        schedule.ran();
+        assert_eq!(schedule.next_run_at(), Some(datetime!(2026-03-06 09:00 UTC)));
+
+        schedule.cancel();
+        assert_eq!(schedule.next_run_at(), None);
+    }
+}
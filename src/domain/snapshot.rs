@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{domain::{balances::Balances, types::AccountId}, store::StreamVersion};
+
+/// An account's balances after folding the first `version` events of its stream.
+///
+/// Replaying only the events after `version` on top of `balances` gives the same
+/// result as replaying the whole stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub account_id: AccountId,
+    pub balances: Balances,
+    pub version: StreamVersion,
+}
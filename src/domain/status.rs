@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Where an account is in its lifecycle. Every account starts open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountStatus {
+    #[default]
+    Open,
+    /// Nothing can be taken out. Money can only come in if `allow_credits` is set.
+    Frozen { allow_credits: bool },
+    /// Closed for good at a zero balance. Nothing can move in or out.
+    Closed,
+}
+
+impl AccountStatus {
+    pub fn is_open(&self) -> bool {
+        *self == AccountStatus::Open
+    }
+
+    pub fn allows_credits(&self) -> bool {
+        match self {
+            AccountStatus::Open => true,
+            AccountStatus::Frozen { allow_credits } => *allow_credits,
+            AccountStatus::Closed => false,
+        }
+    }
+}
@@ -2,3 +2,8 @@ use uuid::Uuid;
 
 pub type AccountId = Uuid;
 pub type EventId = Uuid;
+pub type TransferId = Uuid;
+pub type ConversionId = Uuid;
+pub type HoldId = Uuid;
+pub type EntryId = Uuid;
+pub type ScheduleId = Uuid;
@@ -0,0 +1,42 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{chart::AccountType, details::AccountDetails, errors::DomainError, ledger::Ledger, status::AccountStatus, types::AccountId}};
+
+#[derive(Serialize)]
+pub struct AccountResponse {
+    account_id: AccountId,
+    account_type: AccountType,
+    /// `status` is `OPEN`, `FROZEN` or `CLOSED`, with `allow_credits` when frozen.
+    #[serde(flatten)]
+    status: AccountStatus,
+    #[serde(flatten)]
+    details: AccountDetails,
+}
+
+/// The account's status and details, as returned by every endpoint describing an account.
+pub(super) fn describe_account(ledger: &Ledger, account_id: AccountId) -> Result<AccountResponse, (StatusCode, String)> {
+    let describe = || -> Result<AccountResponse, DomainError> {
+        Ok(AccountResponse {
+            account_id,
+            account_type: ledger.account_type(account_id)?,
+            status: ledger.account_status(account_id)?,
+            details: ledger.account_details(account_id)?,
+        })
+    };
+
+    describe().map_err(<(StatusCode, String)>::from)
+}
+
+pub async fn account_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+) -> Result<(StatusCode, Json<AccountResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    Ok((StatusCode::OK, Json(describe_account(&ledger_guard, account_uuid)?)))
+}
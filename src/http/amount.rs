@@ -0,0 +1,20 @@
+use axum::http::StatusCode;
+
+use crate::domain::{Currency, Money, locale::Locale};
+
+/// Read a request's amount, given either in minor units as `amount_minor` or as a
+/// decimal `amount` string such as `"12.34"` or `"£1,234.56"`.
+pub fn parse_amount(amount_minor: Option<i64>, amount: Option<&str>, currency: Currency) -> Result<Money, (StatusCode, String)> {
+    let money = match (amount_minor, amount) {
+        (Some(amount_minor), None) => Money::new_minor(amount_minor, currency),
+        (None, Some(amount)) => Locale::default().parse(amount, currency),
+        (Some(_), Some(_)) => {
+            return Err((StatusCode::BAD_REQUEST, "Give either amount or amount_minor, not both".to_string()))
+        }
+        (None, None) => {
+            return Err((StatusCode::BAD_REQUEST, "Missing amount or amount_minor".to_string()))
+        }
+    };
+
+    money.map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid amount {e}")))
+}
@@ -1,36 +1,116 @@
-use axum::{Json, extract::{Path, State}, http::StatusCode};
-use serde::Serialize;
+use axum::{Json, extract::{Path, Query, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 
-use crate::{AppState, domain::types::AccountId};
+use crate::{AppState, domain::{MoneyError, ledger::AsOf, locale::Locale, types::AccountId}, http::value_date::parse_value_date};
+
+#[derive(Deserialize)]
+pub struct BalanceQuery {
+    /// RFC 3339 timestamp, or a number of events into the account's stream.
+    as_of: Option<String>,
+    /// Balance on this day, `YYYY-MM-DD`, by value date. With a timestamp `as_of`, as the
+    /// ledger knew it then.
+    value_date: Option<String>,
+    /// Format `display` for this locale, e.g. `de-DE`.
+    locale: Option<String>,
+}
 
 #[derive(Serialize)]
-pub struct BalanceResponse {
-    account_id: AccountId,
+pub struct CurrencyBalance {
+    /// Negative when the account is overdrawn.
     amount_minor: i64,
     currency: String,
     display: String,
+    overdraft_limit_minor: i64,
+    /// Reserved by open holds, still included in `amount_minor`.
+    held_minor: i64,
+    /// What can still be taken out: the balance plus the overdraft limit, less holds.
+    available_minor: i64,
+}
+
+#[derive(Serialize)]
+pub struct BalanceResponse {
+    account_id: AccountId,
+    balances: Vec<CurrencyBalance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    as_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value_date: Option<String>,
+}
+
+fn parse_as_of(value: &str) -> Option<AsOf> {
+    if let Ok(version) = value.parse() {
+        return Some(AsOf::Version(version));
+    }
+
+    OffsetDateTime::parse(value, &Rfc3339).ok().map(AsOf::Time)
 }
 
 pub async fn balance_handler(
     State(state): State<AppState>,
     Path(account_id): Path<String>,
+    Query(query): Query<BalanceQuery>,
 ) -> Result<(StatusCode, Json<BalanceResponse>), (StatusCode, String)> {
     let account_uuid = 
         account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
 
+    let as_of = query.as_of
+        .as_deref()
+        .map(|value| parse_as_of(value).ok_or_else(|| {
+            (StatusCode::BAD_REQUEST, format!("Invalid as_of {value}: expected an RFC 3339 timestamp or event sequence"))
+        }))
+        .transpose()?;
+
+    let as_of = match (parse_value_date(query.value_date.as_deref())?, as_of) {
+        (Some(date), Some(AsOf::Time(known_at))) => Some(AsOf::ValueDate { date, known_at }),
+        (Some(date), None) => Some(AsOf::ValueDate { date, known_at: OffsetDateTime::now_utc() }),
+        (Some(_), Some(_)) => {
+            return Err((StatusCode::BAD_REQUEST, "value_date can only be combined with a timestamp as_of".to_string()))
+        }
+        (None, as_of) => as_of,
+    };
+
+    let locale = query.locale
+        .as_deref()
+        .map(|value| value.parse::<Locale>().map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported locale: {value}"))))
+        .transpose()?;
+
     let ledger_guard = 
         state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
 
-    let balance = ledger_guard.balance_for_account(account_uuid).map_err(
-        |_| (StatusCode::INTERNAL_SERVER_ERROR, "Balance unavailable".to_string())
-    )?;
+    let balances = match as_of {
+        Some(as_of) => ledger_guard.balance_at(account_uuid, as_of),
+        None => ledger_guard.balance_for_account(account_uuid),
+    }?;
+
+    let unreportable = |err| match err {
+        MoneyError::Overflow => (StatusCode::UNPROCESSABLE_ENTITY, "Holds are too large to report".to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Balance unavailable".to_string()),
+    };
+
+    let currency_balances = balances
+        .iter()
+        .map(|balance| {
+            Ok(CurrencyBalance {
+                amount_minor: balance.amount(),
+                currency: balance.currency().code().to_string(),
+                display: match locale {
+                    Some(locale) => balance.format(locale),
+                    None => balance.to_string(),
+                },
+                overdraft_limit_minor: balances.overdraft_limit(balance.currency()).amount(),
+                held_minor: balances.held(balance.currency()).map_err(unreportable)?.amount(),
+                available_minor: balances.available(balance.currency()).map_err(unreportable)?.amount(),
+            })
+        })
+        .collect::<Result<_, (StatusCode, String)>>()?;
 
     let response = BalanceResponse {
         account_id: account_uuid,
-        amount_minor: balance.amount(),
-        currency: balance.currency().code().to_string(),
-        display: balance.to_string()
+        balances: currency_balances,
+        as_of: query.as_of,
+        value_date: query.value_date,
     };
     
     Ok((StatusCode::CREATED, Json(response)))
-}
\ No newline at end of file
+}
@@ -0,0 +1,51 @@
+use axum::{Json, extract::{Query, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, http::statement::{CurrencyAmount, StatementLineResponse, StatementQuery, parse_period}};
+
+#[derive(Serialize)]
+pub struct BalanceSheetResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    /// The balances are as of this instant, or now if not given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    /// Whether assets equal liabilities plus equity plus earnings in every currency.
+    balanced: bool,
+    assets: Vec<StatementLineResponse>,
+    liabilities: Vec<StatementLineResponse>,
+    equity: Vec<StatementLineResponse>,
+    /// Income less expenses from before `from`.
+    retained_earnings: Vec<CurrencyAmount>,
+    /// Income less expenses from `from` to `to`.
+    net_income: Vec<CurrencyAmount>,
+}
+
+pub async fn balance_sheet_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatementQuery>,
+) -> Result<(StatusCode, Json<BalanceSheetResponse>), (StatusCode, String)> {
+    let (from, to) = parse_period(&query)?;
+
+    let ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let sheet = ledger_guard.balance_sheet(from, to)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error building balance sheet".to_string()))?;
+
+    let balanced = sheet.is_balanced()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error building balance sheet".to_string()))?;
+
+    let response = BalanceSheetResponse {
+        from: query.from,
+        to: query.to,
+        balanced,
+        assets: sheet.assets.iter().map(StatementLineResponse::from).collect(),
+        liabilities: sheet.liabilities.iter().map(StatementLineResponse::from).collect(),
+        equity: sheet.equity.iter().map(StatementLineResponse::from).collect(),
+        retained_earnings: sheet.retained_earnings.iter().map(CurrencyAmount::from).collect(),
+        net_income: sheet.net_income.iter().map(CurrencyAmount::from).collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -0,0 +1,69 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, MoneyError, errors::DomainError, types::{AccountId, HoldId}}, http::amount::parse_amount};
+
+/// Leave out the amount to capture the whole hold.
+#[derive(Deserialize)]
+pub struct CaptureRequest {
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    /// Required with an amount.
+    currency: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CaptureResponse {
+    id: uuid::Uuid,
+    hold_id: HoldId,
+    account_id: AccountId,
+}
+
+pub async fn capture_handler(
+    State(state): State<AppState>,
+    Path((account_id, hold_id)): Path<(String, String)>,
+    Json(body): Json<CaptureRequest>,
+) -> Result<(StatusCode, Json<CaptureResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let hold_uuid: HoldId =
+        hold_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid hold id".to_string()))?;
+
+    let money = match (body.amount_minor, body.amount.as_deref(), body.currency) {
+        (None, None, _) => None,
+        (amount_minor, amount, Some(currency)) => {
+            let currency = currency
+                .parse::<Currency>()
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {currency}")))?;
+
+            Some(parse_amount(amount_minor, amount, currency)?)
+        }
+        (_, _, None) => {
+            return Err((StatusCode::BAD_REQUEST, "Missing currency".to_string()))
+        }
+    };
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.capture(account_uuid, hold_uuid, money)
+        .map_err(|err| match err {
+            DomainError::CaptureExceedsHold { held_minor, .. } => {
+                (StatusCode::BAD_REQUEST, format!("Capture exceeds the hold of {held_minor}"))
+            }
+            DomainError::InvalidMoney(MoneyError::CurrencyMismatch(held, _)) => {
+                (StatusCode::BAD_REQUEST, format!("Hold is in {}", held.code()))
+            }
+            other => other.into(),
+        })?;
+
+    let response = CaptureResponse {
+        id,
+        hold_id: hold_uuid,
+        account_id: account_uuid,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
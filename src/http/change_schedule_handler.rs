@@ -0,0 +1,37 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::types::ScheduleId};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleChange {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+#[derive(Serialize)]
+pub struct ScheduleChangeResponse {
+    id: uuid::Uuid,
+    schedule_id: ScheduleId,
+}
+
+pub async fn change_schedule_handler(
+    State(state): State<AppState>,
+    Path((schedule_id, change)): Path<(String, ScheduleChange)>,
+) -> Result<(StatusCode, Json<ScheduleChangeResponse>), (StatusCode, String)> {
+    let schedule_uuid =
+        schedule_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid schedule id".to_string()))?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = match change {
+        ScheduleChange::Pause => ledger_guard.pause_schedule(schedule_uuid)?,
+        ScheduleChange::Resume => ledger_guard.resume_schedule(schedule_uuid)?,
+        ScheduleChange::Cancel => ledger_guard.cancel_schedule(schedule_uuid)?,
+    };
+
+    Ok((StatusCode::OK, Json(ScheduleChangeResponse { id, schedule_id: schedule_uuid })))
+}
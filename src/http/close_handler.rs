@@ -0,0 +1,34 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{errors::DomainError, types::AccountId}};
+
+#[derive(Serialize)]
+pub struct CloseResponse {
+    id: uuid::Uuid,
+    account_id: AccountId,
+}
+
+pub async fn close_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+) -> Result<(StatusCode, Json<CloseResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.close(account_uuid)
+        .map_err(|err| match err {
+            DomainError::AccountNotSettled => {
+                (StatusCode::CONFLICT, "Account must have a zero balance and no open holds to close".to_string())
+            }
+            DomainError::SystemAccount => {
+                (StatusCode::FORBIDDEN, "System accounts can't be closed".to_string())
+            }
+            other => other.into(),
+        })?;
+
+    Ok((StatusCode::OK, Json(CloseResponse { id, account_id: account_uuid })))
+}
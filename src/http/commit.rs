@@ -0,0 +1,26 @@
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use crate::domain::{chain::EventHash, ledger::Ledger};
+
+/// Query options accepted by endpoints that commit events.
+#[derive(Deserialize)]
+pub struct CommitQuery {
+    /// Return the ledger's Merkle root as of this commit.
+    #[serde(default)]
+    include_root: bool,
+}
+
+impl CommitQuery {
+    /// The Merkle root, if asked for. Call it under the same lock as the commit, so no
+    /// later event can be covered by it.
+    pub fn merkle_root(&self, ledger: &Ledger) -> Result<Option<EventHash>, (StatusCode, String)> {
+        if !self.include_root {
+            return Ok(None);
+        }
+
+        let (_, root) = ledger.merkle_root().map_err(<(StatusCode, String)>::from)?;
+
+        Ok(Some(root))
+    }
+}
@@ -0,0 +1,91 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, errors::DomainError, fx::FxError, types::{AccountId, ConversionId}}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct ConversionRequest {
+    from_account_id: String,
+    /// Defaults to `from_account_id`, converting within the account.
+    to_account_id: Option<String>,
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    currency: String,
+    target_currency: String,
+}
+
+#[derive(Serialize)]
+pub struct ConvertedAmount {
+    amount_minor: i64,
+    currency: String,
+}
+
+#[derive(Serialize)]
+pub struct ConversionResponse {
+    conversion_id: ConversionId,
+    from_account_id: AccountId,
+    to_account_id: AccountId,
+    source: ConvertedAmount,
+    target: ConvertedAmount,
+    rate: String,
+    rate_source: String,
+}
+
+pub async fn conversion_handler(
+    State(state): State<AppState>,
+    Json(body): Json<ConversionRequest>,
+) -> Result<(StatusCode, Json<ConversionResponse>), (StatusCode, String)> {
+    let from_uuid: AccountId =
+        body.from_account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid from account id".to_string()))?;
+
+    let to_uuid: AccountId = match &body.to_account_id {
+        Some(to) => to.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid to account id".to_string()))?,
+        None => from_uuid,
+    };
+
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
+
+    let target_currency = body.target_currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.target_currency)))?;
+
+    let money = parse_amount(body.amount_minor, body.amount.as_deref(), currency)?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let conversion = ledger_guard.convert(from_uuid, to_uuid, money, target_currency)
+        .map_err(|err| match err {
+            DomainError::Fx(FxError::NoRate(from, to)) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("No rate from {} to {}", from.code(), to.code()))
+            }
+            DomainError::Fx(FxError::Overflow) => {
+                (StatusCode::BAD_REQUEST, "Converted amount is too large".to_string())
+            }
+            DomainError::Fx(FxError::TooSmall) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "Amount is too small to convert".to_string())
+            }
+            other => other.into(),
+        })?;
+
+    let response = ConversionResponse {
+        conversion_id: conversion.conversion_id,
+        from_account_id: from_uuid,
+        to_account_id: to_uuid,
+        source: ConvertedAmount {
+            amount_minor: conversion.source.amount(),
+            currency: conversion.source.currency().code().to_string(),
+        },
+        target: ConvertedAmount {
+            amount_minor: conversion.target.amount(),
+            currency: conversion.target.currency().code().to_string(),
+        },
+        rate: conversion.rate.to_string(),
+        rate_source: conversion.rate_source,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
@@ -1,12 +1,16 @@
-use axum::{Json, extract::{Path, State}, http::StatusCode};
+use axum::{Json, extract::{Path, Query, State}, http::StatusCode};
 use serde::{Deserialize, Serialize};
 
-use crate::{AppState, domain::{Currency, Money, errors::DomainError, types::AccountId}};
+use crate::{AppState, domain::{Currency, chain::EventHash, types::AccountId}, http::{amount::parse_amount, commit::CommitQuery, value_date::parse_value_date}};
 
 #[derive(Deserialize)]
 pub struct DepositRequest {
-    amount_minor: i64,
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
     currency: String,
+    /// The day the money counts from, `YYYY-MM-DD`. Defaults to the day it is booked.
+    value_date: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -15,42 +19,43 @@ pub struct DepositResponse {
     account_id: AccountId,
     amount_minor: i64,
     currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merkle_root: Option<EventHash>,
 }
 
 pub async fn deposit_handler(
     State(state): State<AppState>,
     Path(account_id): Path<String>,
+    Query(query): Query<CommitQuery>,
     Json(body): Json<DepositRequest>,
 ) -> Result<(StatusCode, Json<DepositResponse>), (StatusCode, String)> {
     let account_uuid = 
         account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
 
-    let currency = match body.currency.as_str() {
-        "GBP" => Currency::Gbp,
-        other => {
-            return Err((StatusCode::BAD_REQUEST, format!("Unsupported currency: {other}")))
-        }
-    };
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
 
-    let money = Money::new_minor(body.amount_minor, currency)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid amount {e}")))?;
+    let money = parse_amount(body.amount_minor, body.amount.as_deref(), currency)?;
+
+    let value_date = parse_value_date(body.value_date.as_deref())?;
 
     let mut ledger_guard = 
         state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
 
-    let id = ledger_guard.deposit(account_uuid, money)
-        .map_err(|err| match err {
-            DomainError::AccountNotFound => {
-                (StatusCode::NOT_FOUND, "Account not found".to_string())
-            }
-            _ =>(StatusCode::INTERNAL_SERVER_ERROR, "Ledger error".to_string())
-        })?;
+    let id = ledger_guard.deposit_with_value_date(account_uuid, money, value_date)?;
+
+    let merkle_root = query.merkle_root(&ledger_guard)?;
 
     let response = DepositResponse {
         id,
         account_id: account_uuid,
-        amount_minor: body.amount_minor,
+        amount_minor: money.amount(),
         currency: body.currency,
+        value_date: body.value_date,
+        merkle_root,
     };
     
     Ok((StatusCode::CREATED, Json(response)))
@@ -0,0 +1,43 @@
+use axum::http::StatusCode;
+
+use crate::domain::{MoneyError, errors::DomainError};
+
+/// How a ledger error is reported to clients, unless a handler has something more
+/// specific to say about it.
+impl From<DomainError> for (StatusCode, String) {
+    fn from(err: DomainError) -> Self {
+        match err {
+            DomainError::AccountNotFound => (StatusCode::NOT_FOUND, "Account not found".to_string()),
+            DomainError::EventNotFound => (StatusCode::NOT_FOUND, "Event not found".to_string()),
+            DomainError::HoldNotFound => (StatusCode::NOT_FOUND, "Hold not found".to_string()),
+            DomainError::InsufficientFunds { .. } => (StatusCode::BAD_REQUEST, "Insufficient funds".to_string()),
+            DomainError::InvalidMoney(MoneyError::Overflow) => {
+                (StatusCode::BAD_REQUEST, "Balance would be too large".to_string())
+            }
+            DomainError::SelfTransfer => (StatusCode::BAD_REQUEST, "Cannot transfer to the same account".to_string()),
+            DomainError::AccountFrozen => (StatusCode::FORBIDDEN, "Account is frozen".to_string()),
+            DomainError::SystemAccount => {
+                (StatusCode::FORBIDDEN, "System accounts only move through journal entries".to_string())
+            }
+            DomainError::JournalOnly(account_type) => {
+                (StatusCode::FORBIDDEN, format!("Only journal entries can move {account_type} accounts"))
+            }
+            DomainError::AccountClosed => (StatusCode::GONE, "Account is closed".to_string()),
+            DomainError::ExternalIdTaken(external_id) => {
+                (StatusCode::CONFLICT, format!("External id {external_id} is already in use"))
+            }
+            DomainError::ScheduleNotFound => (StatusCode::NOT_FOUND, "Schedule not found".to_string()),
+            DomainError::SchedulePaused => (StatusCode::CONFLICT, "Schedule is already paused".to_string()),
+            DomainError::ScheduleNotPaused => (StatusCode::CONFLICT, "Schedule is not paused".to_string()),
+            DomainError::ScheduleCancelled => (StatusCode::GONE, "Schedule is cancelled".to_string()),
+            DomainError::InvalidSchedule(reason) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Invalid schedule: {reason}"))
+            }
+            err @ DomainError::ValueDateOutOfRange { .. } => (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()),
+            DomainError::ConcurrencyConflict { .. } => {
+                (StatusCode::CONFLICT, "Account was modified concurrently, please retry".to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "Ledger error".to_string()),
+        }
+    }
+}
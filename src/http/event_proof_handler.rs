@@ -0,0 +1,38 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{chain::EventHash, merkle::ProofStep, types::EventId}};
+
+#[derive(Serialize)]
+pub struct EventProofResponse {
+    event_id: EventId,
+    event_hash: EventHash,
+    leaf_index: u64,
+    tree_size: u64,
+    path: Vec<ProofStep>,
+    root: EventHash,
+}
+
+pub async fn event_proof_handler(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>,
+) -> Result<(StatusCode, Json<EventProofResponse>), (StatusCode, String)> {
+    let event_uuid = 
+        event_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid event id".to_string()))?;
+
+    let ledger_guard = 
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let proof = ledger_guard.inclusion_proof(event_uuid)?;
+
+    let response = EventProofResponse {
+        event_id: event_uuid,
+        event_hash: proof.event_hash,
+        leaf_index: proof.leaf_index,
+        tree_size: proof.tree_size,
+        path: proof.path,
+        root: proof.root,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
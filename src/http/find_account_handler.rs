@@ -0,0 +1,21 @@
+use axum::{Json, extract::{Query, State}, http::StatusCode};
+use serde::Deserialize;
+
+use crate::{AppState, http::account_handler::{AccountResponse, describe_account}};
+
+#[derive(Deserialize)]
+pub struct FindAccountQuery {
+    external_id: String,
+}
+
+pub async fn find_account_handler(
+    State(state): State<AppState>,
+    Query(query): Query<FindAccountQuery>,
+) -> Result<(StatusCode, Json<AccountResponse>), (StatusCode, String)> {
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let account_id = ledger_guard.find_by_external_id(&query.external_id)?;
+
+    Ok((StatusCode::OK, Json(describe_account(&ledger_guard, account_id)?)))
+}
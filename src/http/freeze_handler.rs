@@ -0,0 +1,48 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{errors::DomainError, types::AccountId}};
+
+#[derive(Deserialize, Default)]
+pub struct FreezeRequest {
+    /// Keep accepting deposits and incoming transfers while frozen.
+    #[serde(default)]
+    allow_credits: bool,
+}
+
+#[derive(Serialize)]
+pub struct FreezeResponse {
+    id: uuid::Uuid,
+    account_id: AccountId,
+    allow_credits: bool,
+}
+
+pub async fn freeze_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    body: Option<Json<FreezeRequest>>,
+) -> Result<(StatusCode, Json<FreezeResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let Json(body) = body.unwrap_or_default();
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.freeze(account_uuid, body.allow_credits)
+        .map_err(|err| match err {
+            DomainError::SystemAccount => {
+                (StatusCode::FORBIDDEN, "System accounts can't be frozen".to_string())
+            }
+            other => other.into(),
+        })?;
+
+    let response = FreezeResponse {
+        id,
+        account_id: account_uuid,
+        allow_credits: body.allow_credits,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -1,22 +1,48 @@
-use axum::{Json, extract::{Path, State}, http::StatusCode};
+use std::collections::HashMap;
 
-use crate::{AppState, domain::{errors::DomainError, events::LedgerEvent}};
+use axum::{extract::{Path, State}, http::{StatusCode, header}, response::IntoResponse};
+use serde::Serialize;
+
+use crate::{AppState, domain::{events::{LedgerEvent, LedgerEventPayload}, types::EventId}};
+
+#[derive(Serialize)]
+struct EventResponse<'a> {
+    #[serde(flatten)]
+    event: &'a LedgerEvent,
+    /// The reversal undoing this event, which in turn names it in `reverses`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reversed_by: Option<EventId>,
+}
 
 pub async fn get_account_events_handler(
     State(state): State<AppState>,
     Path(account_id): Path<String>
-) -> Result<Json<Vec<LedgerEvent>>, (StatusCode, String)> {
+) -> Result<impl IntoResponse, (StatusCode, String)> {
     let account_uuid = 
         account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
 
     let ledger_guard = 
         state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
 
-    let events = ledger_guard.events_for_account(account_uuid)
-        .map_err(|err|  match err {
-            DomainError::AccountNotFound => (StatusCode::NOT_FOUND, "Account not found".to_string()),
-            _ =>(StatusCode::INTERNAL_SERVER_ERROR, "Error fetching events".to_string())
-        })?;
+    let events = ledger_guard.events_for_account(account_uuid)?;
+
+    // Reversals are recorded on the same account as the event they undo.
+    let reversed_by: HashMap<EventId, EventId> = events
+        .iter()
+        .filter_map(|event| match event.payload {
+            LedgerEventPayload::EventReversed { reverses, .. } => Some((reverses, event.id)),
+            _ => None,
+        })
+        .collect();
+
+    let response: Vec<_> = events
+        .iter()
+        .map(|event| EventResponse { event, reversed_by: reversed_by.get(&event.id).copied() })
+        .collect();
+
+    // Serialize while the lock is held so the events are borrowed from the ledger, not cloned.
+    let body = serde_json::to_vec(&response)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error fetching events".to_string()))?;
 
-    Ok(Json(events))
-}
\ No newline at end of file
+    Ok(([(header::CONTENT_TYPE, "application/json")], body))
+}
@@ -0,0 +1,49 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, types::{AccountId, HoldId}}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct HoldRequest {
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    currency: String,
+}
+
+#[derive(Serialize)]
+pub struct HoldResponse {
+    hold_id: HoldId,
+    account_id: AccountId,
+    amount_minor: i64,
+    currency: String,
+}
+
+pub async fn hold_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(body): Json<HoldRequest>,
+) -> Result<(StatusCode, Json<HoldResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
+
+    let money = parse_amount(body.amount_minor, body.amount.as_deref(), currency)?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let hold_id = ledger_guard.authorize(account_uuid, money)?;
+
+    let response = HoldResponse {
+        hold_id,
+        account_id: account_uuid,
+        amount_minor: money.amount(),
+        currency: body.currency,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
@@ -0,0 +1,42 @@
+use axum::{Json, extract::{Query, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, http::statement::{CurrencyAmount, StatementLineResponse, StatementQuery, parse_period}};
+
+#[derive(Serialize)]
+pub struct IncomeStatementResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    income: Vec<StatementLineResponse>,
+    expenses: Vec<StatementLineResponse>,
+    /// Income less expenses per currency, negative for a loss.
+    net_income: Vec<CurrencyAmount>,
+}
+
+pub async fn income_statement_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatementQuery>,
+) -> Result<(StatusCode, Json<IncomeStatementResponse>), (StatusCode, String)> {
+    let (from, to) = parse_period(&query)?;
+
+    let ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let statement = ledger_guard.income_statement(from, to)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error building income statement".to_string()))?;
+
+    let net_income = statement.net_income()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error building income statement".to_string()))?;
+
+    let response = IncomeStatementResponse {
+        from: query.from,
+        to: query.to,
+        income: statement.income.iter().map(StatementLineResponse::from).collect(),
+        expenses: statement.expenses.iter().map(StatementLineResponse::from).collect(),
+        net_income: net_income.iter().map(CurrencyAmount::from).collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
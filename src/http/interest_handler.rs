@@ -0,0 +1,65 @@
+use axum::{Json, extract::{Path, Query, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{AppState, domain::{Currency, types::AccountId}, http::value_date::parse_date};
+
+#[derive(Deserialize)]
+pub struct InterestQuery {
+    currency: String,
+    /// Annual rate in hundredths of a percent, e.g. `365` for 3.65%.
+    rate_bps: u32,
+    /// First day to earn interest, `YYYY-MM-DD`.
+    from: String,
+    /// Day to stop before, `YYYY-MM-DD`. Defaults to today.
+    to: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct InterestResponse {
+    account_id: AccountId,
+    currency: String,
+    rate_bps: u32,
+    from: String,
+    to: String,
+    interest_minor: i64,
+}
+
+pub async fn interest_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Query(query): Query<InterestQuery>,
+) -> Result<(StatusCode, Json<InterestResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let currency = query.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", query.currency)))?;
+
+    let from = parse_date("from", &query.from)?;
+    let to = match query.to.as_deref() {
+        Some(to) => parse_date("to", to)?,
+        None => OffsetDateTime::now_utc().date(),
+    };
+
+    if to < from {
+        return Err((StatusCode::BAD_REQUEST, "to can't be before from".to_string()))
+    }
+
+    let ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let interest = ledger_guard.accrued_interest(account_uuid, currency, query.rate_bps, from, to)?;
+
+    let response = InterestResponse {
+        account_id: account_uuid,
+        currency: query.currency,
+        rate_bps: query.rate_bps,
+        from: query.from,
+        to: to.to_string(),
+        interest_minor: interest.amount(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -0,0 +1,70 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, MoneyError, errors::DomainError, journal::{JournalEntry, JournalLeg, Side}, types::{AccountId, EntryId}}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct JournalLegRequest {
+    account_id: String,
+    /// `DEBIT` or `CREDIT`.
+    side: Side,
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    currency: String,
+}
+
+#[derive(Deserialize)]
+pub struct JournalEntryRequest {
+    legs: Vec<JournalLegRequest>,
+    memo: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct JournalEntryResponse {
+    entry_id: EntryId,
+    accounts: Vec<AccountId>,
+}
+
+pub async fn journal_entry_handler(
+    State(state): State<AppState>,
+    Json(body): Json<JournalEntryRequest>,
+) -> Result<(StatusCode, Json<JournalEntryResponse>), (StatusCode, String)> {
+    let mut legs = Vec::with_capacity(body.legs.len());
+
+    for leg in body.legs {
+        let account_id: AccountId =
+            leg.account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid account id: {}", leg.account_id)))?;
+
+        let currency = leg.currency
+            .parse::<Currency>()
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", leg.currency)))?;
+
+        let amount = parse_amount(leg.amount_minor, leg.amount.as_deref(), currency)?;
+
+        legs.push(JournalLeg { account_id, side: leg.side, amount });
+    }
+
+    let entry = JournalEntry::new(legs, body.memo)
+        .map_err(|err| match err {
+            DomainError::UnbalancedEntry { currency, debits_minor, credits_minor } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Entry does not balance in {}: debits {debits_minor}, credits {credits_minor}", currency.code()))
+            }
+            DomainError::InvalidEntry(reason) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid entry: {reason}"))
+            }
+            DomainError::InvalidMoney(MoneyError::Overflow) => {
+                (StatusCode::BAD_REQUEST, "Entry total is too large".to_string())
+            }
+            other => other.into(),
+        })?;
+
+    let accounts = entry.accounts();
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let entry_id = ledger_guard.post_entry(entry)?;
+
+    Ok((StatusCode::CREATED, Json(JournalEntryResponse { entry_id, accounts })))
+}
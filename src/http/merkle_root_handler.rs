@@ -0,0 +1,22 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::chain::EventHash};
+
+#[derive(Serialize)]
+pub struct MerkleRootResponse {
+    tree_size: u64,
+    root: EventHash,
+}
+
+pub async fn merkle_root_handler(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<MerkleRootResponse>), (StatusCode, String)> {
+    let ledger_guard = 
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let (tree_size, root) = ledger_guard.merkle_root()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error computing root".to_string()))?;
+
+    Ok((StatusCode::OK, Json(MerkleRootResponse { tree_size, root })))
+}
@@ -1,10 +1,39 @@
 pub mod routes;
 
+mod amount;
+mod commit;
+mod error;
+mod statement;
+mod value_date;
+
 mod health_handler;
 mod new_account_handler;
 mod get_account_events_handler;
 mod deposit_handler;
 mod balance_handler;
 mod withdrawal_handler;
+mod verify_chain_handler;
+mod merkle_root_handler;
+mod event_proof_handler;
+mod transfer_handler;
+mod conversion_handler;
+mod overdraft_handler;
+mod hold_handler;
+mod capture_handler;
+mod void_handler;
+mod account_handler;
+mod freeze_handler;
+mod unfreeze_handler;
+mod close_handler;
+mod update_account_handler;
+mod find_account_handler;
+mod journal_entry_handler;
+mod trial_balance_handler;
+mod balance_sheet_handler;
+mod income_statement_handler;
+mod interest_handler;
+mod reverse_handler;
+mod schedule_handler;
+mod change_schedule_handler;
 
 pub use routes::create_router;
\ No newline at end of file
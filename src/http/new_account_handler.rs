@@ -1,20 +1,74 @@
+use std::collections::BTreeSet;
+
 use axum::{Json, extract::State, http::StatusCode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, chart::AccountType, details::AccountDetails, errors::DomainError, types::AccountId}};
 
-use crate::{AppState, domain::types::AccountId};
+#[derive(Deserialize)]
+pub struct NewAccountRequest {
+    currency: Option<String>,
+    /// `ASSET`, `LIABILITY`, `EQUITY`, `INCOME` or `EXPENSE`. Customer wallets are liabilities.
+    account_type: Option<AccountType>,
+    owner_ref: Option<String>,
+    display_name: Option<String>,
+    #[serde(default)]
+    labels: BTreeSet<String>,
+    external_id: Option<String>,
+    code: Option<String>,
+    parent_code: Option<String>,
+}
 
 #[derive(Serialize)]
 pub struct NewAccountResponse {
-    id: AccountId
+    id: AccountId,
+    currency: String,
+    account_type: AccountType,
+    #[serde(flatten)]
+    details: AccountDetails,
 }
 
 pub async fn new_account_handler(
-    State(state): State<AppState>
-) -> (StatusCode, Json<NewAccountResponse>) {
+    State(state): State<AppState>,
+    body: Option<Json<NewAccountRequest>>,
+) -> Result<(StatusCode, Json<NewAccountResponse>), (StatusCode, String)> {
+    // Accounts opened without a currency hold GBP, as they always have.
+    let (currency, account_type, details) = match body {
+        Some(Json(body)) => {
+            let currency = match body.currency {
+                Some(currency) => currency
+                    .parse::<Currency>()
+                    .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {currency}")))?,
+                None => Currency::Gbp,
+            };
+
+            let details = AccountDetails {
+                owner_ref: body.owner_ref.filter(|v| !v.is_empty()),
+                display_name: body.display_name.filter(|v| !v.is_empty()),
+                labels: body.labels,
+                external_id: body.external_id.filter(|v| !v.is_empty()),
+                code: body.code.filter(|v| !v.is_empty()),
+                parent_code: body.parent_code.filter(|v| !v.is_empty()),
+            };
+
+            (currency, body.account_type.unwrap_or_default(), details)
+        }
+        None => (Currency::Gbp, AccountType::Liability, AccountDetails::default()),
+    };
+
     let mut ledger_guard = 
-        state.ledger.lock().expect("Mutex poisoned");
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
 
-    let account_id = ledger_guard.open_account();
+    let account_id = ledger_guard.open_account_of_type(currency, account_type, details.clone())
+        .map_err(|err| match err {
+            DomainError::AccountCodeTaken(code) => {
+                (StatusCode::CONFLICT, format!("Account code {code} is already in use"))
+            }
+            DomainError::InvalidParent(reason) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Invalid parent account: {reason}"))
+            }
+            other => other.into(),
+        })?;
 
-    (StatusCode::CREATED, Json(NewAccountResponse { id: account_id }))
-}
\ No newline at end of file
+    Ok((StatusCode::CREATED, Json(NewAccountResponse { id: account_id, currency: currency.code().to_string(), account_type, details })))
+}
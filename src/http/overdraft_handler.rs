@@ -0,0 +1,58 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, errors::DomainError, types::AccountId}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct OverdraftRequest {
+    limit_minor: Option<i64>,
+    /// Decimal alternative to `limit_minor`, e.g. `"500.00"`.
+    limit: Option<String>,
+    currency: String,
+}
+
+#[derive(Serialize)]
+pub struct OverdraftResponse {
+    id: uuid::Uuid,
+    account_id: AccountId,
+    limit_minor: i64,
+    currency: String,
+}
+
+pub async fn overdraft_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(body): Json<OverdraftRequest>,
+) -> Result<(StatusCode, Json<OverdraftResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
+
+    let limit = parse_amount(body.limit_minor, body.limit.as_deref(), currency)?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.set_overdraft_limit(account_uuid, limit)
+        .map_err(|err| match err {
+            DomainError::SystemAccount => {
+                (StatusCode::FORBIDDEN, "System accounts can't have an overdraft".to_string())
+            }
+            DomainError::JournalOnly(account_type) => {
+                (StatusCode::FORBIDDEN, format!("Only customer wallets can have an overdraft, not {account_type} accounts"))
+            }
+            other => other.into(),
+        })?;
+
+    let response = OverdraftResponse {
+        id,
+        account_id: account_uuid,
+        limit_minor: limit.amount(),
+        currency: body.currency,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -0,0 +1,45 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{errors::DomainError, types::EventId}};
+
+#[derive(Deserialize)]
+pub struct ReverseRequest {
+    reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ReverseResponse {
+    reversal_id: EventId,
+    reverses: EventId,
+    reason: String,
+}
+
+pub async fn reverse_handler(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>,
+    Json(body): Json<ReverseRequest>,
+) -> Result<(StatusCode, Json<ReverseResponse>), (StatusCode, String)> {
+    let event_uuid =
+        event_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid event id".to_string()))?;
+
+    if body.reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "A reason is required".to_string()))
+    }
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let reversal_id = ledger_guard.reverse(event_uuid, &body.reason)
+        .map_err(|err| match err {
+            DomainError::AlreadyReversed(reversal_id) => {
+                (StatusCode::CONFLICT, format!("Event was already reversed by {reversal_id}"))
+            }
+            DomainError::NotReversible(reason) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Cannot reverse: {reason}"))
+            }
+            other => other.into(),
+        })?;
+
+    Ok((StatusCode::CREATED, Json(ReverseResponse { reversal_id, reverses: event_uuid, reason: body.reason })))
+}
@@ -1,19 +1,40 @@
 use std::net::SocketAddr;
 
 use axum::{
-    Router, routing::{get, post}
+    Router, routing::{get, post, put}
 };
 
-use crate::{AppState, http::{balance_handler::balance_handler, deposit_handler::deposit_handler, get_account_events_handler::get_account_events_handler, health_handler::health_handler, new_account_handler::new_account_handler, withdrawal_handler::withdrawal_handler}};
+use crate::{AppState, http::{account_handler::account_handler, balance_handler::balance_handler, balance_sheet_handler::balance_sheet_handler, capture_handler::capture_handler, change_schedule_handler::change_schedule_handler, close_handler::close_handler, conversion_handler::conversion_handler, deposit_handler::deposit_handler, event_proof_handler::event_proof_handler, find_account_handler::find_account_handler, freeze_handler::freeze_handler, get_account_events_handler::get_account_events_handler, health_handler::health_handler, hold_handler::hold_handler, income_statement_handler::income_statement_handler, interest_handler::interest_handler, journal_entry_handler::journal_entry_handler, merkle_root_handler::merkle_root_handler, new_account_handler::new_account_handler, overdraft_handler::overdraft_handler, reverse_handler::reverse_handler, schedule_handler::schedule_handler, transfer_handler::transfer_handler, trial_balance_handler::trial_balance_handler, unfreeze_handler::unfreeze_handler, update_account_handler::update_account_handler, verify_chain_handler::verify_chain_handler, void_handler::void_handler, withdrawal_handler::withdrawal_handler}};
 
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health_handler))
-        .route("/accounts", post(new_account_handler))
+        .route("/accounts", get(find_account_handler).post(new_account_handler))
+        .route("/accounts/{account_id}", get(account_handler).patch(update_account_handler))
         .route("/accounts/{account_id}/events", get(get_account_events_handler))
         .route("/accounts/{account_id}/deposit", post(deposit_handler))
         .route("/accounts/{account_id}/withdraw", post(withdrawal_handler))
         .route("/accounts/{account_id}/balance", get(balance_handler))
+        .route("/accounts/{account_id}/interest", get(interest_handler))
+        .route("/accounts/{account_id}/overdraft", put(overdraft_handler))
+        .route("/accounts/{account_id}/holds", post(hold_handler))
+        .route("/accounts/{account_id}/holds/{hold_id}/capture", post(capture_handler))
+        .route("/accounts/{account_id}/holds/{hold_id}/void", post(void_handler))
+        .route("/accounts/{account_id}/freeze", post(freeze_handler))
+        .route("/accounts/{account_id}/unfreeze", post(unfreeze_handler))
+        .route("/accounts/{account_id}/close", post(close_handler))
+        .route("/accounts/{account_id}/schedules", post(schedule_handler))
+        .route("/transfers", post(transfer_handler))
+        .route("/conversions", post(conversion_handler))
+        .route("/journal-entries", post(journal_entry_handler))
+        .route("/ledger/trial-balance", get(trial_balance_handler))
+        .route("/ledger/balance-sheet", get(balance_sheet_handler))
+        .route("/ledger/income-statement", get(income_statement_handler))
+        .route("/ledger/verify", get(verify_chain_handler))
+        .route("/ledger/root", get(merkle_root_handler))
+        .route("/events/{event_id}/proof", get(event_proof_handler))
+        .route("/events/{event_id}/reverse", post(reverse_handler))
+        .route("/schedules/{schedule_id}/{change}", post(change_schedule_handler))
         .with_state(state)
 }
 
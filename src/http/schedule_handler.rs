@@ -0,0 +1,80 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::{AppState, domain::{Currency, schedule::{Frequency, ScheduledPayment}, types::{AccountId, ScheduleId}}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct ScheduleRequest {
+    /// Transfer each run to this account. Without it each run is a withdrawal.
+    to_account_id: Option<String>,
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    currency: String,
+    frequency: Frequency,
+    /// RFC 3339 timestamp of the first run. Defaults to now.
+    starts_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ScheduleResponse {
+    schedule_id: ScheduleId,
+    account_id: AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_account_id: Option<AccountId>,
+    amount_minor: i64,
+    currency: String,
+    frequency: Frequency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    starts_at: Option<String>,
+}
+
+pub async fn schedule_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(body): Json<ScheduleRequest>,
+) -> Result<(StatusCode, Json<ScheduleResponse>), (StatusCode, String)> {
+    let account_uuid: AccountId =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let to_uuid: Option<AccountId> = body.to_account_id
+        .as_deref()
+        .map(|to| to.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid to account id".to_string())))
+        .transpose()?;
+
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
+
+    let money = parse_amount(body.amount_minor, body.amount.as_deref(), currency)?;
+
+    let starts_at = body.starts_at
+        .as_deref()
+        .map(|value| OffsetDateTime::parse(value, &Rfc3339).map_err(|_| {
+            (StatusCode::BAD_REQUEST, format!("Invalid starts_at {value}: expected an RFC 3339 timestamp"))
+        }))
+        .transpose()?;
+
+    let payment = match to_uuid {
+        Some(to) => ScheduledPayment::Transfer { to, amount: money },
+        None => ScheduledPayment::Withdrawal { amount: money },
+    };
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let schedule_id = ledger_guard.schedule_payment(account_uuid, payment, body.frequency, starts_at)?;
+
+    let response = ScheduleResponse {
+        schedule_id,
+        account_id: account_uuid,
+        to_account_id: to_uuid,
+        amount_minor: money.amount(),
+        currency: body.currency,
+        frequency: body.frequency,
+        starts_at: body.starts_at,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
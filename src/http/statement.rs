@@ -0,0 +1,68 @@
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::domain::{Currency, SignedMoney, chart::StatementLine, types::AccountId};
+
+#[derive(Deserialize)]
+pub struct StatementQuery {
+    /// RFC 3339 timestamp. Defaults to the start of the ledger.
+    pub from: Option<String>,
+    /// RFC 3339 timestamp. Defaults to now.
+    pub to: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StatementLineResponse {
+    account_id: AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    currency: String,
+    amount_minor: i64,
+}
+
+impl From<&StatementLine> for StatementLineResponse {
+    fn from(line: &StatementLine) -> Self {
+        Self {
+            account_id: line.account_id,
+            code: line.code.clone(),
+            parent_code: line.parent_code.clone(),
+            name: line.name.clone(),
+            currency: line.amount.currency().code().to_string(),
+            amount_minor: line.amount.amount(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CurrencyAmount {
+    currency: String,
+    amount_minor: i64,
+}
+
+impl From<(&Currency, &SignedMoney)> for CurrencyAmount {
+    fn from((currency, amount): (&Currency, &SignedMoney)) -> Self {
+        Self { currency: currency.code().to_string(), amount_minor: amount.amount() }
+    }
+}
+
+/// The period a statement covers, from `from` exclusive to `to` inclusive.
+pub fn parse_period(query: &StatementQuery) -> Result<(OffsetDateTime, OffsetDateTime), (StatusCode, String)> {
+    let parse = |value: &str| {
+        OffsetDateTime::parse(value, &Rfc3339)
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid timestamp {value}: expected RFC 3339")))
+    };
+
+    let from = query.from.as_deref().map(parse).transpose()?.unwrap_or(OffsetDateTime::UNIX_EPOCH);
+    let to = query.to.as_deref().map(parse).transpose()?.unwrap_or_else(OffsetDateTime::now_utc);
+
+    if from > to {
+        return Err((StatusCode::BAD_REQUEST, "from must not be after to".to_string()))
+    }
+
+    Ok((from, to))
+}
@@ -0,0 +1,55 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, domain::{Currency, types::{AccountId, TransferId}}, http::amount::parse_amount};
+
+#[derive(Deserialize)]
+pub struct TransferRequest {
+    from_account_id: String,
+    to_account_id: String,
+    amount_minor: Option<i64>,
+    /// Decimal alternative to `amount_minor`, e.g. `"12.34"` or `"£1,234.56"`.
+    amount: Option<String>,
+    currency: String,
+}
+
+#[derive(Serialize)]
+pub struct TransferResponse {
+    transfer_id: TransferId,
+    from_account_id: AccountId,
+    to_account_id: AccountId,
+    amount_minor: i64,
+    currency: String,
+}
+
+pub async fn transfer_handler(
+    State(state): State<AppState>,
+    Json(body): Json<TransferRequest>,
+) -> Result<(StatusCode, Json<TransferResponse>), (StatusCode, String)> {
+    let from_uuid: AccountId = 
+        body.from_account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid from account id".to_string()))?;
+
+    let to_uuid: AccountId = 
+        body.to_account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid to account id".to_string()))?;
+
+    let currency = body.currency
+        .parse::<Currency>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unsupported currency: {}", body.currency)))?;
+
+    let money = parse_amount(body.amount_minor, body.amount.as_deref(), currency)?;
+
+    let mut ledger_guard = 
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let transfer_id = ledger_guard.transfer(from_uuid, to_uuid, money)?;
+
+    let response = TransferResponse {
+        transfer_id,
+        from_account_id: from_uuid,
+        to_account_id: to_uuid,
+        amount_minor: money.amount(),
+        currency: body.currency,
+    };
+    
+    Ok((StatusCode::CREATED, Json(response)))
+}
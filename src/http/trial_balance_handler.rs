@@ -0,0 +1,63 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{journal::SystemAccount, types::AccountId}};
+
+#[derive(Serialize)]
+pub struct TrialBalanceLineResponse {
+    account_id: AccountId,
+    /// Set for the ledger's own accounts, e.g. `CASH_IN_BANK`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_account: Option<SystemAccount>,
+    currency: String,
+    debit_minor: i64,
+    credit_minor: i64,
+}
+
+#[derive(Serialize)]
+pub struct TrialBalanceTotal {
+    currency: String,
+    debit_minor: i64,
+    credit_minor: i64,
+}
+
+#[derive(Serialize)]
+pub struct TrialBalanceResponse {
+    balanced: bool,
+    lines: Vec<TrialBalanceLineResponse>,
+    totals: Vec<TrialBalanceTotal>,
+}
+
+pub async fn trial_balance_handler(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<TrialBalanceResponse>), (StatusCode, String)> {
+    let ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let trial_balance = ledger_guard.trial_balance()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error building trial balance".to_string()))?;
+
+    let response = TrialBalanceResponse {
+        balanced: trial_balance.is_balanced(),
+        lines: trial_balance.lines
+            .iter()
+            .map(|line| TrialBalanceLineResponse {
+                account_id: line.account_id,
+                system_account: SystemAccount::from_id(line.account_id),
+                currency: line.currency.code().to_string(),
+                debit_minor: line.debit.amount(),
+                credit_minor: line.credit.amount(),
+            })
+            .collect(),
+        totals: trial_balance.totals
+            .iter()
+            .map(|(currency, (debits, credits))| TrialBalanceTotal {
+                currency: currency.code().to_string(),
+                debit_minor: debits.amount(),
+                credit_minor: credits.amount(),
+            })
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -0,0 +1,34 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{errors::DomainError, types::AccountId}};
+
+#[derive(Serialize)]
+pub struct UnfreezeResponse {
+    id: uuid::Uuid,
+    account_id: AccountId,
+}
+
+pub async fn unfreeze_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+) -> Result<(StatusCode, Json<UnfreezeResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.unfreeze(account_uuid)
+        .map_err(|err| match err {
+            DomainError::AccountNotFrozen => {
+                (StatusCode::CONFLICT, "Account is not frozen".to_string())
+            }
+            DomainError::SystemAccount => {
+                (StatusCode::FORBIDDEN, "System accounts can't be unfrozen".to_string())
+            }
+            other => other.into(),
+        })?;
+
+    Ok((StatusCode::OK, Json(UnfreezeResponse { id, account_id: account_uuid })))
+}
@@ -0,0 +1,42 @@
+use std::collections::BTreeSet;
+
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Deserialize;
+
+use crate::{AppState, domain::details::AccountDetailsChanges, http::account_handler::{AccountResponse, describe_account}};
+
+/// Only the fields given are changed. An empty string clears a field; `labels` replaces every label.
+#[derive(Deserialize)]
+pub struct UpdateAccountRequest {
+    owner_ref: Option<String>,
+    display_name: Option<String>,
+    labels: Option<BTreeSet<String>>,
+    external_id: Option<String>,
+}
+
+pub async fn update_account_handler(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(body): Json<UpdateAccountRequest>,
+) -> Result<(StatusCode, Json<AccountResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let changes = AccountDetailsChanges {
+        owner_ref: body.owner_ref,
+        display_name: body.display_name,
+        labels: body.labels,
+        external_id: body.external_id,
+    };
+
+    if changes == AccountDetailsChanges::default() {
+        return Err((StatusCode::BAD_REQUEST, "Nothing to update".to_string()))
+    }
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    ledger_guard.update_details(account_uuid, changes)?;
+
+    Ok((StatusCode::OK, Json(describe_account(&ledger_guard, account_uuid)?)))
+}
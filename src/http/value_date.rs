@@ -0,0 +1,13 @@
+use axum::http::StatusCode;
+use time::{Date, macros::format_description};
+
+/// Read an optional value date given as `YYYY-MM-DD`.
+pub fn parse_value_date(value_date: Option<&str>) -> Result<Option<Date>, (StatusCode, String)> {
+    value_date.map(|value| parse_date("value_date", value)).transpose()
+}
+
+/// Read the date given as `YYYY-MM-DD` for the parameter `name`.
+pub fn parse_date(name: &str, value: &str) -> Result<Date, (StatusCode, String)> {
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid {name} {value}: expected YYYY-MM-DD")))
+}
@@ -0,0 +1,45 @@
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::{chain::BreakReason, types::EventId}};
+
+#[derive(Serialize)]
+pub struct BrokenLinkResponse {
+    position: u64,
+    event_id: EventId,
+    reason: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct VerifyChainResponse {
+    valid: bool,
+    events_checked: u64,
+    first_broken_link: Option<BrokenLinkResponse>,
+}
+
+pub async fn verify_chain_handler(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<VerifyChainResponse>), (StatusCode, String)> {
+    let ledger_guard = 
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let (events_checked, first_break) = ledger_guard.verify_chain()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Error verifying ledger".to_string()))?;
+
+    let first_broken_link = first_break.map(|broken| BrokenLinkResponse {
+        position: broken.position,
+        event_id: broken.event_id,
+        reason: match broken.reason {
+            BreakReason::BrokenLink => "prev_hash does not match the preceding event",
+            BreakReason::HashMismatch => "hash does not match the event contents",
+        },
+    });
+
+    let response = VerifyChainResponse {
+        valid: first_broken_link.is_none(),
+        events_checked,
+        first_broken_link,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
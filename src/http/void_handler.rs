@@ -0,0 +1,35 @@
+use axum::{Json, extract::{Path, State}, http::StatusCode};
+use serde::Serialize;
+
+use crate::{AppState, domain::types::{AccountId, HoldId}};
+
+#[derive(Serialize)]
+pub struct VoidResponse {
+    id: uuid::Uuid,
+    hold_id: HoldId,
+    account_id: AccountId,
+}
+
+pub async fn void_handler(
+    State(state): State<AppState>,
+    Path((account_id, hold_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<VoidResponse>), (StatusCode, String)> {
+    let account_uuid =
+        account_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account id".to_string()))?;
+
+    let hold_uuid: HoldId =
+        hold_id.parse().map_err(|_| (StatusCode::BAD_REQUEST, "Invalid hold id".to_string()))?;
+
+    let mut ledger_guard =
+        state.ledger.lock().map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Ledger unavailable".to_string()))?;
+
+    let id = ledger_guard.void(account_uuid, hold_uuid)?;
+
+    let response = VoidResponse {
+        id,
+        hold_id: hold_uuid,
+        account_id: account_uuid,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -8,16 +8,25 @@
 mod http;
 mod config;
 mod domain;
+mod store;
 
-use std::sync::{Arc, Mutex};
+use std::{sync::{Arc, Mutex}, time::Duration};
 
-use crate::domain::ledger::{Ledger};
-use crate::{config::Config, http::routes::format_listen_addr};
+use crate::domain::{fx::RateTable, ledger::Ledger};
+use crate::{config::{Config, Storage}, http::routes::format_listen_addr};
 use crate::http::create_router;
+use crate::store::{JournalEventStore, SqliteEventStore};
 
-use tracing::{info, Level};
+use time::OffsetDateTime;
+use tracing::{info, warn, Level};
 use tracing_subscriber::EnvFilter;
-use anyhow::Result;
+use anyhow::{Context, Result};
+
+/// How often the background task looks for holds to expire.
+const HOLD_EXPIRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the background task looks for scheduled payments that have fallen due.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct AppState {
@@ -32,11 +41,26 @@ async fn main() -> Result<()>{
 
     info!(?config, "Loaded Configuration");
 
-    let ledger = Ledger::new();
+    let ledger = match &config.storage {
+        Storage::InMemory => Ledger::new(),
+        Storage::Sqlite(path) => Ledger::with_store(SqliteEventStore::open(path)?),
+        Storage::Journal(path) => Ledger::with_store(
+            JournalEventStore::open(path)
+                .with_context(|| format!("failed to open journal {}", path.display()))?,
+        ),
+    }
+    .with_snapshot_interval(config.snapshot_interval)
+    .with_rates(load_rates(&config)?)
+    .with_hold_ttl(config.hold_ttl)
+    .with_value_date_window(config.max_backdate, config.max_forward_date);
+
     let app_state = AppState {
         ledger: Arc::new(Mutex::new(ledger))
     };
 
+    tokio::spawn(expire_holds(app_state.ledger.clone()));
+    tokio::spawn(run_scheduled_payments(app_state.ledger.clone()));
+
     let app = create_router(app_state);
     let address = format_listen_addr(config.http_port);
     let listener = tokio::net::TcpListener::bind(address).await?;
@@ -48,6 +72,58 @@ async fn main() -> Result<()>{
     Ok(())
 }
 
+/// Release holds that have gone uncaptured for longer than the configured TTL.
+async fn expire_holds(ledger: Arc<Mutex<Ledger>>) {
+    let mut interval = tokio::time::interval(HOLD_EXPIRY_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let Ok(mut ledger) = ledger.lock() else {
+            warn!("Ledger unavailable, stopping hold expiry");
+            return;
+        };
+
+        match ledger.expire_holds(OffsetDateTime::now_utc()) {
+            Ok(0) => {}
+            Ok(expired) => info!("Expired {} holds", expired),
+            Err(err) => warn!("Failed to expire holds: {}", err),
+        }
+    }
+}
+
+/// Make the runs of standing orders as they fall due. The first check is straight away,
+/// catching up on any runs missed while the service was down.
+async fn run_scheduled_payments(ledger: Arc<Mutex<Ledger>>) {
+    let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let Ok(mut ledger) = ledger.lock() else {
+            warn!("Ledger unavailable, stopping scheduled payments");
+            return;
+        };
+
+        match ledger.run_due_payments(OffsetDateTime::now_utc()) {
+            Ok(0) => {}
+            Ok(runs) => info!("Ran {} scheduled payments", runs),
+            Err(err) => warn!("Failed to run scheduled payments: {}", err),
+        }
+    }
+}
+
+fn load_rates(config: &Config) -> Result<RateTable> {
+    let Some(path) = &config.fx_rates_path else {
+        return Ok(RateTable::default());
+    };
+
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read rate table {}", path.display()))?;
+
+    RateTable::from_json(&data).with_context(|| format!("failed to load rate table {}", path.display()))
+}
+
 fn init_tracing() {
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("mini_ledger=info"));
@@ -0,0 +1,278 @@
+use std::{
+    borrow::Cow,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::Path,
+};
+
+use tracing::warn;
+
+use crate::{
+    domain::{chain, events::LedgerEvent, snapshot::BalanceSnapshot, types::AccountId},
+    store::{EventStore, InMemoryEventStore, StoreError, StreamVersion, schema},
+};
+
+/// Durable event store that writes one JSON line per append to an append-only file:
+/// a single event as an object, or an atomic batch of events as an array.
+///
+/// The journal is replayed into memory on open and every append is fsynced
+/// before it returns. A final line without a trailing newline is the remains
+/// of an interrupted write and is truncated away; anything else that fails to
+/// parse is treated as corruption.
+///
+/// An append whose write or fsync fails is cut back off the file. If even that fails
+/// the store refuses further appends, rather than writing after a torn line that would
+/// stop the journal from opening again.
+///
+/// Snapshots are only kept in memory: the whole journal is read on open anyway.
+#[derive(Debug)]
+pub struct JournalEventStore {
+    file: File,
+    /// Length of the file up to the end of the last complete append.
+    len: u64,
+    poisoned: bool,
+    events: InMemoryEventStore,
+}
+
+impl JournalEventStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+
+        let mut events = InMemoryEventStore::new();
+        let mut valid_len = 0;
+
+        for (index, line) in contents.split_inclusive(|b| *b == b'\n').enumerate() {
+            if !line.ends_with(b"\n") {
+                warn!("Truncating torn final line of journal {}", path.display());
+                file.set_len(valid_len as u64)?;
+                file.sync_all()?;
+                break;
+            }
+
+            let batch = schema::decode_batch(line)
+                .map_err(|source| StoreError::CorruptJournal { line: index + 1, source })?;
+
+            events.append_sealed(&[], batch)?;
+
+            valid_len += line.len();
+        }
+
+        Ok(Self { file, len: valid_len as u64, poisoned: false, events })
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> Result<(), StoreError> {
+        if let Err(err) = self.file.write_all(line).and_then(|()| self.file.sync_data()) {
+            // Part of the line may have reached the file. Cut it off so the next append
+            // doesn't land after it.
+            if self.file.set_len(self.len).and_then(|()| self.file.sync_all()).is_err() {
+                self.poisoned = true;
+            }
+
+            return Err(err.into());
+        }
+
+        self.len += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+impl EventStore for JournalEventStore {
+    fn append_all(
+        &mut self,
+        expected_versions: &[(AccountId, StreamVersion)],
+        events: &[LedgerEvent],
+    ) -> Result<(), StoreError> {
+        if self.poisoned {
+            return Err(StoreError::JournalPoisoned);
+        }
+
+        self.events.check_versions(expected_versions)?;
+
+        let sealed = chain::seal_all(self.events.head_hash(), events);
+
+        // One line per batch, so a crash can only ever tear off a whole batch.
+        let mut line = schema::encode_batch(&sealed)?;
+        line.push('\n');
+
+        self.write_line(line.as_bytes())?;
+
+        self.events.append_sealed(&[], sealed)
+    }
+
+    fn read_stream_from(&self, account_id: AccountId, version: StreamVersion) -> Result<Cow<'_, [LedgerEvent]>, StoreError> {
+        self.events.read_stream_from(account_id, version)
+    }
+
+    fn read_all_from(&self, position: u64) -> Result<Vec<LedgerEvent>, StoreError> {
+        self.events.read_all_from(position)
+    }
+
+    fn load_snapshot(&self, account_id: AccountId) -> Result<Option<BalanceSnapshot>, StoreError> {
+        self.events.load_snapshot(account_id)
+    }
+
+    fn save_snapshot(&mut self, snapshot: &BalanceSnapshot) -> Result<(), StoreError> {
+        self.events.save_snapshot(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::domain::{Currency, Money};
+
+    fn temp_journal() -> PathBuf {
+        std::env::temp_dir().join(format!("mini-ledger-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn events_survive_reopen() {
+        let path = temp_journal();
+        let account_id = AccountId::new_v4();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+            store.append(account_id, 1, &[LedgerEvent::deposit(account_id, Money::new_minor(5_00, Currency::Gbp).unwrap())]).unwrap();
+        }
+
+        let mut store = JournalEventStore::open(&path).unwrap();
+        assert_eq!(store.read_stream(account_id).unwrap().len(), 2);
+
+        // Stream versions are rebuilt too, so stale writers are still caught.
+        let err = store.append(account_id, 1, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap_err();
+        assert!(matches!(err, StoreError::VersionConflict { expected: 1, actual: 2, .. }));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn torn_final_line_is_truncated() {
+        let path = temp_journal();
+        let account_id = AccountId::new_v4();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+        }
+        let intact_len = std::fs::metadata(&path).unwrap().len();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"id":"a1b2","account_"#).unwrap();
+
+        let store = JournalEventStore::open(&path).unwrap();
+        assert_eq!(store.read_all_from(0).unwrap().len(), 1);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), intact_len);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn edits_to_the_file_are_caught_by_chain_verification() {
+        let path = temp_journal();
+        let account_id = AccountId::new_v4();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+            store.append(account_id, 1, &[LedgerEvent::deposit(account_id, Money::new_minor(5_00, Currency::Gbp).unwrap())]).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, contents.replace(r#""amount":500"#, r#""amount":50000"#)).unwrap();
+
+        let store = JournalEventStore::open(&path).unwrap();
+        let broken = crate::domain::chain::verify(&store.read_all_from(0).unwrap()).unwrap();
+        assert_eq!(broken.position, 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn batch_is_restored_whole_or_torn_off_whole() {
+        let path = temp_journal();
+        let (alice, bob) = (AccountId::new_v4(), AccountId::new_v4());
+        let amount = Money::new_minor(1_00, Currency::Gbp).unwrap();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            store.append_all(&[], &[LedgerEvent::account_opened(alice, Currency::Gbp), LedgerEvent::account_opened(bob, Currency::Gbp)]).unwrap();
+        }
+        let intact = std::fs::read_to_string(&path).unwrap();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            assert_eq!(store.read_all_from(0).unwrap().len(), 2);
+
+            let (debit, credit) = LedgerEvent::transfer(uuid::Uuid::new_v4(), alice, bob, amount, None);
+            store.append_all(&[(alice, 1), (bob, 1)], &[debit, credit]).unwrap();
+        }
+
+        // Cut the transfer's line off halfway, as a crash mid-write would.
+        let full = std::fs::read_to_string(&path).unwrap();
+        let torn_at = intact.len() + (full.len() - intact.len()) / 2;
+        std::fs::write(&path, &full[..torn_at]).unwrap();
+
+        let store = JournalEventStore::open(&path).unwrap();
+        assert_eq!(store.read_all_from(0).unwrap().len(), 2);
+        assert!(store.read_stream(alice).unwrap().len() == 1 && store.read_stream(bob).unwrap().len() == 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn failed_write_that_cannot_be_undone_stops_further_appends() {
+        let path = temp_journal();
+        let account_id = AccountId::new_v4();
+
+        let mut store = JournalEventStore::open(&path).unwrap();
+        store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+
+        // A read-only handle fails the write and the truncation alike.
+        store.file = File::open(&path).unwrap();
+
+        let deposit = LedgerEvent::deposit(account_id, Money::new_minor(5_00, Currency::Gbp).unwrap());
+        assert!(matches!(store.append(account_id, 1, std::slice::from_ref(&deposit)).unwrap_err(), StoreError::Io(_)));
+        assert_eq!(store.read_stream(account_id).unwrap().len(), 1);
+
+        store.file = OpenOptions::new().append(true).open(&path).unwrap();
+        assert!(matches!(store.append(account_id, 1, &[deposit]).unwrap_err(), StoreError::JournalPoisoned));
+
+        assert_eq!(JournalEventStore::open(&path).unwrap().read_all_from(0).unwrap().len(), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn corrupted_middle_record_is_rejected() {
+        let path = temp_journal();
+        let account_id = AccountId::new_v4();
+
+        {
+            let mut store = JournalEventStore::open(&path).unwrap();
+            store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"not json\n").unwrap();
+        serde_json::to_writer(&mut file, &LedgerEvent::account_opened(AccountId::new_v4(), Currency::Gbp)).unwrap();
+        file.write_all(b"\n").unwrap();
+
+        let err = JournalEventStore::open(&path).unwrap_err();
+        assert!(matches!(err, StoreError::CorruptJournal { line: 2, .. }));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{
+    domain::{chain::{self, EventHash}, events::LedgerEvent, snapshot::BalanceSnapshot, types::AccountId},
+    store::{EventStore, StoreError, StreamVersion},
+};
+
+/// Event store that keeps everything in memory. Nothing survives a restart.
+#[derive(Debug, Default)]
+pub struct InMemoryEventStore {
+    /// Each account's events in append order, so a stream can be lent out as one slice.
+    streams: HashMap<AccountId, Vec<LedgerEvent>>,
+    /// Global append order, as (account, index into that account's stream).
+    log: Vec<(AccountId, usize)>,
+    snapshots: HashMap<AccountId, BalanceSnapshot>,
+}
+
+impl InMemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stream_version(&self, account_id: AccountId) -> StreamVersion {
+        self.streams.get(&account_id).map_or(0, |stream| stream.len() as StreamVersion)
+    }
+
+    /// Hash of the last event in the log, which the next appended event links to.
+    pub fn head_hash(&self) -> EventHash {
+        self.log
+            .last()
+            .map_or(EventHash::ZERO, |(account_id, index)| self.streams[account_id][*index].hash)
+    }
+
+    /// Fail if any listed stream has moved past its expected version.
+    pub fn check_versions(&self, expected_versions: &[(AccountId, StreamVersion)]) -> Result<(), StoreError> {
+        for &(account_id, expected) in expected_versions {
+            let actual = self.stream_version(account_id);
+
+            if actual != expected {
+                return Err(StoreError::VersionConflict { account_id, expected, actual });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append events exactly as given, without sealing them onto the chain.
+    /// Used to restore events that were sealed when first written.
+    pub fn append_sealed(
+        &mut self,
+        expected_versions: &[(AccountId, StreamVersion)],
+        events: Vec<LedgerEvent>,
+    ) -> Result<(), StoreError> {
+        self.check_versions(expected_versions)?;
+
+        for event in events {
+            let stream = self.streams.entry(event.account_id).or_default();
+            self.log.push((event.account_id, stream.len()));
+            stream.push(event);
+        }
+
+        Ok(())
+    }
+}
+
+impl EventStore for InMemoryEventStore {
+    fn append_all(
+        &mut self,
+        expected_versions: &[(AccountId, StreamVersion)],
+        events: &[LedgerEvent],
+    ) -> Result<(), StoreError> {
+        let sealed = chain::seal_all(self.head_hash(), events);
+
+        self.append_sealed(expected_versions, sealed)
+    }
+
+    fn read_stream_from(&self, account_id: AccountId, version: StreamVersion) -> Result<Cow<'_, [LedgerEvent]>, StoreError> {
+        let stream = self.streams.get(&account_id).map_or(&[][..], Vec::as_slice);
+        let start = usize::try_from(version).unwrap_or(usize::MAX).min(stream.len());
+
+        Ok(Cow::Borrowed(&stream[start..]))
+    }
+
+    fn read_all_from(&self, position: u64) -> Result<Vec<LedgerEvent>, StoreError> {
+        let start = usize::try_from(position).unwrap_or(usize::MAX).min(self.log.len());
+
+        Ok(self.log[start..]
+            .iter()
+            .map(|(account_id, index)| self.streams[account_id][*index].clone())
+            .collect())
+    }
+
+    fn load_snapshot(&self, account_id: AccountId) -> Result<Option<BalanceSnapshot>, StoreError> {
+        Ok(self.snapshots.get(&account_id).cloned())
+    }
+
+    fn save_snapshot(&mut self, snapshot: &BalanceSnapshot) -> Result<(), StoreError> {
+        let current = self.snapshots.entry(snapshot.account_id).or_insert_with(|| snapshot.clone());
+
+        if current.version < snapshot.version {
+            *current = snapshot.clone();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Currency;
+
+    #[test]
+    fn append_with_stale_version_is_rejected() {
+        let mut store = InMemoryEventStore::new();
+        let account_id = AccountId::new_v4();
+
+        let version = store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+        assert_eq!(version, 1);
+
+        let err = store.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap_err();
+        assert!(matches!(err, StoreError::VersionConflict { expected: 0, actual: 1, .. }));
+
+        assert_eq!(store.read_stream(account_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn read_all_from_skips_earlier_events() {
+        let mut store = InMemoryEventStore::new();
+        let first = AccountId::new_v4();
+        let second = AccountId::new_v4();
+
+        store.append(first, 0, &[LedgerEvent::account_opened(first, Currency::Gbp)]).unwrap();
+        store.append(second, 0, &[LedgerEvent::account_opened(second, Currency::Gbp)]).unwrap();
+
+        let events = store.read_all_from(1).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].account_id, second);
+
+        assert!(store.read_all_from(10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+pub mod journal;
+pub mod memory;
+pub mod schema;
+pub mod sqlite;
+
+use std::{borrow::Cow, fmt::Debug};
+
+use thiserror::Error;
+
+use crate::domain::{events::LedgerEvent, snapshot::BalanceSnapshot, types::AccountId};
+
+pub use journal::JournalEventStore;
+pub use memory::InMemoryEventStore;
+pub use sqlite::SqliteEventStore;
+
+/// Number of events in an account's stream. A stream that doesn't exist yet is at version 0.
+pub type StreamVersion = u64;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("failed to (de)serialize event: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("journal is corrupt at line {line}: {source}")]
+    CorruptJournal {
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("journal has a partial write that could not be removed, so nothing more can be appended until it is reopened")]
+    JournalPoisoned,
+
+    #[error("stream version conflict on {account_id}: expected {expected}, actual {actual}")]
+    VersionConflict {
+        account_id: AccountId,
+        expected: StreamVersion,
+        actual: StreamVersion,
+    },
+}
+
+/// Append-only storage for ledger events, organised as one stream per account.
+pub trait EventStore: Debug + Send {
+    /// Append events atomically, provided every listed stream is still at its expected
+    /// version. Each event goes to the stream of its own `account_id`.
+    fn append_all(
+        &mut self,
+        expected_versions: &[(AccountId, StreamVersion)],
+        events: &[LedgerEvent],
+    ) -> Result<(), StoreError>;
+
+    /// Append events to an account's stream, provided nobody else has appended to it
+    /// since `expected_version` was read. Returns the new stream version.
+    fn append(
+        &mut self,
+        account_id: AccountId,
+        expected_version: StreamVersion,
+        events: &[LedgerEvent],
+    ) -> Result<StreamVersion, StoreError> {
+        self.append_all(&[(account_id, expected_version)], events)?;
+
+        Ok(expected_version + events.len() as StreamVersion)
+    }
+
+    /// All events for one account, oldest first.
+    fn read_stream(&self, account_id: AccountId) -> Result<Cow<'_, [LedgerEvent]>, StoreError> {
+        self.read_stream_from(account_id, 0)
+    }
+
+    /// Events for one account after the first `version` of them, oldest first.
+    /// Stores that hold events in memory lend them out instead of cloning.
+    fn read_stream_from(&self, account_id: AccountId, version: StreamVersion) -> Result<Cow<'_, [LedgerEvent]>, StoreError>;
+
+    /// Every event across all accounts, skipping the first `position` events.
+    fn read_all_from(&self, position: u64) -> Result<Vec<LedgerEvent>, StoreError>;
+
+    /// The most recent balance snapshot saved for an account, if any.
+    fn load_snapshot(&self, account_id: AccountId) -> Result<Option<BalanceSnapshot>, StoreError>;
+
+    /// Save a snapshot, replacing an older one for the same account.
+    fn save_snapshot(&mut self, snapshot: &BalanceSnapshot) -> Result<(), StoreError>;
+}
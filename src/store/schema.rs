@@ -0,0 +1,173 @@
+//! Versioned on-disk representation of ledger events.
+//!
+//! Stores write every event tagged with `schema_version`. On read, older
+//! records are upcast one version at a time until they match the current
+//! `LedgerEvent` shape, so changes to `LedgerEventPayload` don't strand
+//! events that are already persisted.
+
+use serde::de::Error as _;
+use serde_json::Value;
+
+use crate::domain::{chain::EventHash, events::LedgerEvent};
+
+pub const CURRENT_SCHEMA_VERSION: u64 = 3;
+
+/// Records written before versioning was introduced carry no tag.
+const UNVERSIONED: u64 = 1;
+
+/// Rewrites a record in place from one schema version to the next.
+type Upcaster = fn(&mut Value);
+
+/// `UPCASTERS[n]` lifts a record from version `n + 1` to `n + 2`.
+const UPCASTERS: [Upcaster; (CURRENT_SCHEMA_VERSION - 1) as usize] = [
+    rename_withdraw_to_withdrawal,
+    add_unsealed_hashes,
+];
+
+/// Serialize an event in the current schema.
+pub fn encode(event: &LedgerEvent) -> serde_json::Result<String> {
+    serde_json::to_string(&encode_value(event)?)
+}
+
+/// Serialize a batch of events as one record: the event itself when there is only
+/// one, otherwise an array of them.
+pub fn encode_batch(events: &[LedgerEvent]) -> serde_json::Result<String> {
+    match events {
+        [event] => encode(event),
+        _ => serde_json::to_string(&events.iter().map(encode_value).collect::<serde_json::Result<Vec<_>>>()?),
+    }
+}
+
+fn encode_value(event: &LedgerEvent) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(event)?;
+
+    if let Value::Object(fields) = &mut value {
+        fields.insert("schema_version".to_string(), CURRENT_SCHEMA_VERSION.into());
+    }
+
+    Ok(value)
+}
+
+/// Deserialize an event written under any schema version up to the current one.
+pub fn decode(data: &[u8]) -> serde_json::Result<LedgerEvent> {
+    decode_value(serde_json::from_slice(data)?)
+}
+
+/// Deserialize a record written by [`encode_batch`].
+pub fn decode_batch(data: &[u8]) -> serde_json::Result<Vec<LedgerEvent>> {
+    match serde_json::from_slice(data)? {
+        Value::Array(values) => values.into_iter().map(decode_value).collect(),
+        value => Ok(vec![decode_value(value)?]),
+    }
+}
+
+fn decode_value(mut value: Value) -> serde_json::Result<LedgerEvent> {
+    let version = match value.as_object_mut().and_then(|fields| fields.remove("schema_version")) {
+        None => UNVERSIONED,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| serde_json::Error::custom("schema_version must be a positive integer"))?,
+    };
+
+    if !(UNVERSIONED..=CURRENT_SCHEMA_VERSION).contains(&version) {
+        return Err(serde_json::Error::custom(format!("unsupported schema version {version}")));
+    }
+
+    for upcast in &UPCASTERS[(version - UNVERSIONED) as usize..] {
+        upcast(&mut value);
+    }
+
+    serde_json::from_value(value)
+}
+
+/// v2: `WITHDRAW` became `WITHDRAWAL`, matching the documented event types.
+fn rename_withdraw_to_withdrawal(event: &mut Value) {
+    if let Some(kind) = event.pointer_mut("/payload/type")
+        && kind == "WITHDRAW"
+    {
+        *kind = "WITHDRAWAL".into();
+    }
+}
+
+/// v3: events carry `prev_hash` and `hash`. Older events were never sealed, so they
+/// get zero hashes and show up as breaks when the chain is verified.
+///
+/// Hashes cover the current canonical shape of an event, so later upcasters must not
+/// change anything that was hashed or they will break verification of older records.
+fn add_unsealed_hashes(event: &mut Value) {
+    if let Value::Object(fields) = event {
+        for field in ["prev_hash", "hash"] {
+            fields.insert(field.to_string(), EventHash::ZERO.to_string().into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Currency, Money, events::LedgerEventPayload, types::AccountId};
+
+    fn load_fixture(fixture: &str) -> Vec<LedgerEvent> {
+        fixture.lines().map(|line| decode(line.as_bytes()).unwrap()).collect()
+    }
+
+    fn assert_opened_deposit_withdrawal(events: &[LedgerEvent], deposited: i64, withdrawn: i64) {
+        assert!(matches!(events[0].payload, LedgerEventPayload::AccountOpened { .. }));
+
+        match &events[1].payload {
+            LedgerEventPayload::Deposit { amount, .. } => assert_eq!(*amount, Money::new_minor(deposited, Currency::Gbp).unwrap()),
+            other => panic!("expected Deposit event, got {other:?}"),
+        }
+
+        match &events[2].payload {
+            LedgerEventPayload::Withdrawal { amount, .. } => assert_eq!(*amount, Money::new_minor(withdrawn, Currency::Gbp).unwrap()),
+            other => panic!("expected Withdrawal event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn loads_unversioned_v1_fixture() {
+        let events = load_fixture(include_str!("fixtures/schema_v1.jsonl"));
+
+        assert_opened_deposit_withdrawal(&events, 10_00, 2_50);
+        assert_eq!(events[2].id.to_string(), "40dbf49e-2e10-49da-891e-ded3bcf13088");
+    }
+
+    #[test]
+    fn loads_v2_fixture() {
+        let events = load_fixture(include_str!("fixtures/schema_v2.jsonl"));
+
+        assert_opened_deposit_withdrawal(&events, 50_00, 12_50);
+        assert_eq!(events[2].created_at.offset().whole_hours(), 1);
+        assert_eq!(events[2].hash, EventHash::ZERO);
+    }
+
+    #[test]
+    fn loads_v3_fixture_with_intact_chain() {
+        let events = load_fixture(include_str!("fixtures/schema_v3.jsonl"));
+
+        assert_opened_deposit_withdrawal(&events, 20_000, 7_525);
+        assert_eq!(crate::domain::chain::verify(&events), None);
+    }
+
+    #[test]
+    fn encode_tags_current_version_and_round_trips() {
+        let event = LedgerEvent::withdraw(AccountId::new_v4(), Money::new_minor(1_00, Currency::Gbp).unwrap());
+
+        let data = encode(&event).unwrap();
+        let value: Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(value["schema_version"], CURRENT_SCHEMA_VERSION);
+
+        let decoded = decode(data.as_bytes()).unwrap();
+        assert_eq!(decoded.id, event.id);
+        assert_eq!(decoded.created_at, event.created_at);
+    }
+
+    #[test]
+    fn rejects_records_from_a_newer_schema() {
+        let data = br#"{"schema_version":99,"payload":{"type":"ACCOUNT_OPENED"}}"#;
+
+        let err = decode(data).unwrap_err();
+        assert!(err.to_string().contains("unsupported schema version 99"));
+    }
+}
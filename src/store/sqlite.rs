@@ -0,0 +1,381 @@
+use std::{borrow::Cow, collections::HashMap, path::Path};
+
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+use tracing::warn;
+
+use crate::{
+    domain::{chain::{self, EventHash}, events::LedgerEvent, snapshot::BalanceSnapshot, types::AccountId},
+    store::{EventStore, StoreError, StreamVersion, schema},
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        position   INTEGER PRIMARY KEY AUTOINCREMENT,
+        id         TEXT NOT NULL UNIQUE,
+        account_id TEXT NOT NULL,
+        version    INTEGER NOT NULL,
+        data       TEXT NOT NULL,
+        UNIQUE (account_id, version)
+    );
+    CREATE TABLE IF NOT EXISTS snapshots (
+        account_id TEXT PRIMARY KEY,
+        version    INTEGER NOT NULL,
+        data       TEXT NOT NULL
+    );
+";
+
+/// Durable event store backed by a single SQLite table.
+///
+/// Each event is stored as the JSON produced by its serde derives, tagged with
+/// its schema version, so what is read back is exactly what was appended. Several processes may share the
+/// same database file: appends take a write lock and check the stream version.
+#[derive(Debug)]
+pub struct SqliteEventStore {
+    conn: Connection,
+}
+
+impl SqliteEventStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self, StoreError> {
+        conn.execute_batch(SCHEMA)?;
+        Self::add_stream_versions(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Databases created before streams were versioned have no `version` column, and
+    /// `CREATE TABLE IF NOT EXISTS` leaves them as they are. Number each account's events
+    /// in the order they were appended, as reading the stream back always has.
+    fn add_stream_versions(conn: &mut Connection) -> Result<(), StoreError> {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let versioned = tx
+            .prepare("SELECT 1 FROM pragma_table_info('events') WHERE name = 'version'")?
+            .exists([])?;
+
+        if !versioned {
+            tx.execute_batch(
+                "ALTER TABLE events ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+                 UPDATE events SET version = (
+                     SELECT COUNT(*) FROM events AS earlier
+                     WHERE earlier.account_id = events.account_id AND earlier.position <= events.position
+                 );
+                 CREATE UNIQUE INDEX events_account_version ON events (account_id, version);",
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn stream_version(conn: &Connection, account_id: AccountId) -> Result<StreamVersion, StoreError> {
+        let version = conn
+            .query_row(
+                "SELECT MAX(version) FROM events WHERE account_id = ?1",
+                [account_id.to_string()],
+                |row| row.get::<_, Option<StreamVersion>>(0),
+            )
+            .optional()?
+            .flatten()
+            .unwrap_or(0);
+
+        Ok(version)
+    }
+
+    fn query_events(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<LedgerEvent>, StoreError> {
+        let mut query = self.conn.prepare_cached(sql)?;
+
+        let rows = query.query_map(params, |row| row.get::<_, String>(0))?;
+
+        let mut events = Vec::new();
+        for data in rows {
+            events.push(schema::decode(data?.as_bytes())?);
+        }
+
+        Ok(events)
+    }
+}
+
+impl EventStore for SqliteEventStore {
+    /// Append events in a single transaction: either all of them are stored or none are.
+    fn append_all(
+        &mut self,
+        expected_versions: &[(AccountId, StreamVersion)],
+        events: &[LedgerEvent],
+    ) -> Result<(), StoreError> {
+        // IMMEDIATE takes the write lock up front, so the version check and the
+        // inserts can't interleave with another writer.
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let mut versions = HashMap::new();
+
+        for &(account_id, expected) in expected_versions {
+            let actual = Self::stream_version(&tx, account_id)?;
+
+            if actual != expected {
+                return Err(StoreError::VersionConflict { account_id, expected, actual });
+            }
+
+            versions.insert(account_id, actual);
+        }
+
+        // Sealing under the write lock means concurrent writers can't fork the chain.
+        let head = tx
+            .query_row("SELECT data FROM events ORDER BY position DESC LIMIT 1", [], |row| row.get::<_, String>(0))
+            .optional()?
+            .map(|data| schema::decode(data.as_bytes()))
+            .transpose()?
+            .map_or(EventHash::ZERO, |event| event.hash);
+
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO events (id, account_id, version, data) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+
+            for event in &chain::seal_all(head, events) {
+                let version = match versions.get(&event.account_id) {
+                    Some(version) => *version,
+                    None => Self::stream_version(&tx, event.account_id)?,
+                } + 1;
+                versions.insert(event.account_id, version);
+
+                let data = schema::encode(event)?;
+                insert.execute(params![event.id.to_string(), event.account_id.to_string(), version, data])?;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn read_stream_from(&self, account_id: AccountId, version: StreamVersion) -> Result<Cow<'_, [LedgerEvent]>, StoreError> {
+        self.query_events(
+            "SELECT data FROM events WHERE account_id = ?1 AND version > ?2 ORDER BY version",
+            params![account_id.to_string(), version],
+        )
+        .map(Cow::Owned)
+    }
+
+    fn read_all_from(&self, position: u64) -> Result<Vec<LedgerEvent>, StoreError> {
+        // Positions are 1-based rowids, so skipping `position` events means reading past it.
+        self.query_events(
+            "SELECT data FROM events WHERE position > ?1 ORDER BY position",
+            [position],
+        )
+    }
+
+    fn load_snapshot(&self, account_id: AccountId) -> Result<Option<BalanceSnapshot>, StoreError> {
+        let data = self.conn
+            .query_row(
+                "SELECT data FROM snapshots WHERE account_id = ?1",
+                [account_id.to_string()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        // Snapshots are only a cache, so one saved in an older shape is skipped and the
+        // balance is rebuilt by replaying the stream instead.
+        match data.map(|data| serde_json::from_str(&data)).transpose() {
+            Ok(snapshot) => Ok(snapshot),
+            Err(err) => {
+                warn!("Ignoring unreadable snapshot for account {}: {}", account_id, err);
+                Ok(None)
+            }
+        }
+    }
+
+    fn save_snapshot(&mut self, snapshot: &BalanceSnapshot) -> Result<(), StoreError> {
+        let data = serde_json::to_string(snapshot)?;
+
+        // Never let a slower writer replace a newer snapshot with an older one.
+        self.conn.execute(
+            "INSERT INTO snapshots (account_id, version, data) VALUES (?1, ?2, ?3)
+             ON CONFLICT (account_id) DO UPDATE SET version = excluded.version, data = excluded.data
+             WHERE excluded.version > snapshots.version",
+            params![snapshot.account_id.to_string(), snapshot.version, data],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Currency, Money, balances::Balances, chart::AccountType, events::LedgerEventPayload};
+
+    #[test]
+    fn appended_events_round_trip_exactly() {
+        let mut store = SqliteEventStore::open_in_memory().unwrap();
+        let account_id = AccountId::new_v4();
+
+        let opened = LedgerEvent::account_opened(account_id, Currency::Gbp);
+        let deposit = LedgerEvent::deposit(account_id, Money::new_minor(12_34, Currency::Gbp).unwrap());
+
+        store.append(account_id, 0, &[opened.clone(), deposit.clone()]).unwrap();
+
+        let loaded = store.read_all_from(0).unwrap();
+        assert_eq!(loaded.len(), 2);
+
+        for (original, loaded) in [opened, deposit].iter().zip(&loaded) {
+            assert_eq!(original.id, loaded.id);
+            assert_eq!(original.account_id, loaded.account_id);
+            assert_eq!(original.created_at, loaded.created_at);
+        }
+
+        match &loaded[1].payload {
+            LedgerEventPayload::Deposit { amount, .. } => {
+                assert_eq!(amount.amount(), 12_34);
+                assert_eq!(amount.currency(), Currency::Gbp);
+            }
+            other => panic!("expected Deposit event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn failed_append_stores_nothing() {
+        let mut store = SqliteEventStore::open_in_memory().unwrap();
+        let account_id = AccountId::new_v4();
+
+        let opened = LedgerEvent::account_opened(account_id, Currency::Gbp);
+
+        // Appending the same event id twice violates the unique constraint,
+        // so the whole batch must be rolled back.
+        let result = store.append(account_id, 0, &[opened.clone(), opened]);
+        assert!(result.is_err());
+
+        assert!(store.read_all_from(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn second_writer_with_stale_version_conflicts() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+        let mut first = SqliteEventStore::open(&path).unwrap();
+        let mut second = SqliteEventStore::open(&path).unwrap();
+        let account_id = AccountId::new_v4();
+
+        first.append(account_id, 0, &[LedgerEvent::account_opened(account_id, Currency::Gbp)]).unwrap();
+
+        // Both writers read version 1 and try to withdraw.
+        let withdrawal = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        first.append(account_id, 1, &[LedgerEvent::withdraw(account_id, withdrawal)]).unwrap();
+
+        let err = second.append(account_id, 1, &[LedgerEvent::withdraw(account_id, withdrawal)]).unwrap_err();
+        assert!(matches!(err, StoreError::VersionConflict { expected: 1, actual: 2, .. }));
+
+        assert_eq!(second.read_stream(account_id).unwrap().len(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn database_from_before_stream_versions_is_migrated() {
+        let path = std::env::temp_dir().join(format!("mini-ledger-{}.db", uuid::Uuid::new_v4()));
+        let account_id = AccountId::new_v4();
+        let other_id = AccountId::new_v4();
+
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE events (
+                     position   INTEGER PRIMARY KEY AUTOINCREMENT,
+                     id         TEXT NOT NULL UNIQUE,
+                     account_id TEXT NOT NULL,
+                     data       TEXT NOT NULL
+                 );
+                 CREATE INDEX events_account_id ON events (account_id);",
+            ).unwrap();
+
+            let deposit = Money::new_minor(10_00, Currency::Gbp).unwrap();
+            for event in [
+                LedgerEvent::account_opened(account_id, Currency::Gbp),
+                LedgerEvent::account_opened(other_id, Currency::Gbp),
+                LedgerEvent::deposit(account_id, deposit),
+            ] {
+                conn.execute(
+                    "INSERT INTO events (id, account_id, data) VALUES (?1, ?2, ?3)",
+                    params![event.id.to_string(), event.account_id.to_string(), serde_json::to_string(&event).unwrap()],
+                ).unwrap();
+            }
+        }
+
+        let mut store = SqliteEventStore::open(&path).unwrap();
+
+        assert_eq!(store.read_stream(account_id).unwrap().len(), 2);
+        assert_eq!(store.read_stream_from(account_id, 1).unwrap().len(), 1);
+
+        let withdrawal = Money::new_minor(5_00, Currency::Gbp).unwrap();
+        let err = store.append(account_id, 1, &[LedgerEvent::withdraw(account_id, withdrawal)]).unwrap_err();
+        assert!(matches!(err, StoreError::VersionConflict { expected: 1, actual: 2, .. }));
+        store.append(account_id, 2, &[LedgerEvent::withdraw(account_id, withdrawal)]).unwrap();
+        store.append(other_id, 1, &[LedgerEvent::deposit(other_id, withdrawal)]).unwrap();
+
+        // Opening it again finds the column and leaves it alone.
+        drop(store);
+        let store = SqliteEventStore::open(&path).unwrap();
+        assert_eq!(store.read_stream(account_id).unwrap().len(), 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn older_snapshot_does_not_replace_newer_one() {
+        let mut store = SqliteEventStore::open_in_memory().unwrap();
+        let account_id = AccountId::new_v4();
+
+        let snapshot = |version, amount| {
+            let mut balances = Balances::opened_in(Currency::Gbp, AccountType::Liability);
+            balances.credit(Money::new_minor(amount, Currency::Gbp).unwrap()).unwrap();
+            BalanceSnapshot { account_id, balances, version }
+        };
+
+        store.save_snapshot(&snapshot(200, 20_00)).unwrap();
+        store.save_snapshot(&snapshot(100, 10_00)).unwrap();
+
+        assert_eq!(store.load_snapshot(account_id).unwrap(), Some(snapshot(200, 20_00)));
+        assert_eq!(store.load_snapshot(AccountId::new_v4()).unwrap(), None);
+    }
+
+    #[test]
+    fn snapshot_in_an_older_shape_is_ignored() {
+        let store = SqliteEventStore::open_in_memory().unwrap();
+        let account_id = AccountId::new_v4();
+
+        // Snapshots used to hold a single balance rather than one per currency.
+        store.conn.execute(
+            "INSERT INTO snapshots (account_id, version, data) VALUES (?1, 100, ?2)",
+            params![
+                account_id.to_string(),
+                format!(r#"{{"account_id":"{account_id}","balance":{{"amount":500,"currency":"GBP"}},"version":100}}"#),
+            ],
+        ).unwrap();
+
+        assert_eq!(store.load_snapshot(account_id).unwrap(), None);
+    }
+
+    #[test]
+    fn snapshot_from_before_account_types_is_ignored() {
+        let store = SqliteEventStore::open_in_memory().unwrap();
+        let account_id = AccountId::new_v4();
+
+        // Without the type there's no telling which way the balances were signed.
+        store.conn.execute(
+            "INSERT INTO snapshots (account_id, version, data) VALUES (?1, 100, ?2)",
+            params![
+                account_id.to_string(),
+                format!(r#"{{"account_id":"{account_id}","balances":{{"balances":{{"GBP":{{"amount":500,"currency":"GBP"}}}}}},"version":100}}"#),
+            ],
+        ).unwrap();
+
+        assert_eq!(store.load_snapshot(account_id).unwrap(), None);
+    }
+}